@@ -41,6 +41,7 @@ pub fn render(
 fn main() -> Result<(), Box<dyn Error>> {
     Ok(App::new(100)?
         .handle_panics()
-        .widgets((chunk_generator, render))
+        .add_widgets(Stage::PreUpdate, chunk_generator)
+        .add_widgets(Stage::Render, render)
         .run()?)
 }
@@ -42,7 +42,8 @@ fn my_widget(mut events: ResMut<Events>, mut message: ResMut<MessageState>) -> W
 fn main() -> Result<(), Box<dyn Error>> {
     Ok(App::new(100)?
         .handle_panics()
-        .widgets((chunk_builder, my_widget))
         .sets(Message)
+        .add_widgets(Stage::PreUpdate, chunk_builder)
+        .widgets(my_widget)
         .run()?)
 }
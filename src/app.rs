@@ -1,163 +1,1084 @@
 use std::{
-    any::{Any, TypeId},
+    any::{type_name, Any, TypeId},
     cell::RefCell,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
-    io,
+    hash::Hash,
+    io::{self, stdout},
     ops::Deref,
-    time::{Duration, SystemTime},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
-use ratatui::{buffer::Buffer, prelude::Backend};
+use crossterm::{
+    cursor::MoveTo,
+    event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+};
+use ratatui::{
+    buffer::Buffer,
+    prelude::Backend,
+    text::Text,
+    widgets::{Paragraph, Widget as _},
+    Terminal,
+};
 
 use crate::{
+    app_state::{StateMachine, Transitions},
     chunks::Chunks,
-    events::Events,
+    commands::CommandQueue,
+    event_bus::{CurrentWidget, EventQueue},
+    events::{EventSender, Events, UserEvents},
+    input::{EventSource, Input},
+    redraw::{Redraw, RunMode},
     set::{Set, Sets},
-    setup::{reset_terminal, restore_terminal, setup_terminal, WidgetFrame, WidgetTerminal},
-    states::{MultiFromStates, States, Time},
-    widget::{into_widget::IntoWidget, into_widget_set::IntoWidgetSet, Widget},
+    setup::{
+        reset_terminal, resume_terminal, setup_terminal, AppConfig, AppTerminal, DetachedTerminal,
+        WidgetFrame,
+    },
+    stage::Stage,
+    states::{FixedTime, MultiFromStates, States, Time},
+    tasks::{self, Tasks},
+    timer::Timers,
+    tween::Tweens,
+    widget::{
+        id::WidgetId,
+        into_widget::IntoWidget,
+        into_widget_set::IntoWidgetSet,
+        order::{sort_by_order, Label, WidgetOrder},
+        Widget, WidgetError,
+    },
     widgets::message::MessageState,
     Res, ResMut, WidgetParam, WidgetResult,
 };
 
+/// A widget registered to the app, along with everything needed to order and find it.
+struct WidgetEntry {
+    widget: Box<dyn Widget>,
+    order: WidgetOrder,
+    id: WidgetId,
+    enabled: bool,
+}
+
+/// What the app finished with, returned from [`App::run_returning`].
+pub struct AppExit<T> {
+    /// The value given to [`Events::exit_with`], if it was a `T`.
+    pub value: Option<T>,
+    /// Every state, as it was when the app stopped.
+    pub states: States,
+}
+
+/// The most events drained into [`Events`] for a single frame.
+pub(crate) const MAX_EVENTS_PER_FRAME: usize = 256;
+
 /// The powerhouse of widgetui, runs all defined widgets for you
 pub struct App {
-    terminal: WidgetTerminal,
-    widgets: Vec<Box<dyn Widget>>,
+    terminal: Box<dyn AppTerminal>,
+    input: Input,
+    widgets: BTreeMap<Stage, Vec<WidgetEntry>>,
     pub(crate) states: States,
     clock: Duration,
+    /// How the app set the terminal up, or `None` if it was given a terminal to use as is.
+    terminal_config: Option<AppConfig>,
+    started: bool,
+    unsorted: bool,
+    set_orders: Vec<WidgetOrder>,
+    transitions: Vec<Box<dyn Transitions>>,
+    last_frame: Option<Instant>,
+    run_mode: RunMode,
+    event_updates: Vec<fn(&States)>,
+    /// Forgets the readers of each event type that aren't in the given widgets.
+    event_reader_retains: Vec<fn(&States, &HashSet<WidgetId>)>,
+    sets: Vec<Box<dyn Set>>,
+    set_names: HashSet<String>,
+    terminate: Arc<AtomicBool>,
+    suspend_requested: Arc<AtomicBool>,
+    /// The signal actions registered while the app holds the terminal.
+    #[cfg(unix)]
+    signals: Vec<signal_hook::SigId>,
+    ctrl_c_exits: bool,
+    ctrl_z_suspends: bool,
+    command_error: Option<WidgetError>,
 }
 
 impl App {
     /// Create a new app with the given clock time (in ms)
     pub fn new(clock: u64) -> Result<Self, io::Error> {
-        let terminal = setup_terminal()?;
+        Self::with_config(clock, AppConfig::default())
+    }
+
+    /// Create a new app that draws in a viewport of the given height below the cursor,
+    /// instead of taking over the whole screen, with the given clock time (in ms).
+    ///
+    /// Everything drawn is left in the terminal history once the app stops,
+    /// and lines can be added to the history above the viewport with [`App::print_above`].
+    pub fn new_inline(clock: u64, height: u16) -> Result<Self, io::Error> {
+        Self::with_config(clock, AppConfig::default().inline(height))
+    }
+
+    /// Create a new app with the given clock time (in ms), setting the terminal up as configured,
+    /// such as with mouse capture or bracketed paste turned on.
+    ///
+    /// Everything the config turns on is turned back off when the app stops.
+    pub fn with_config(clock: u64, config: AppConfig) -> Result<Self, io::Error> {
+        let terminal = setup_terminal(&config)?;
+
+        let mut app = Self::from_terminal(terminal, clock);
+        app.terminal_config = Some(config);
+        app.ctrl_c_exits = true;
+        app.ctrl_z_suspends = cfg!(unix);
+
+        Ok(app.handle_panics())
+    }
+
+    /// Create a new app that draws to the given terminal, with the given clock time (in ms).
+    ///
+    /// Unlike [`App::new`], the terminal is used as is, so nothing is set up or restored,
+    /// allowing any [`Backend`] to be used, such as ratatui's `TestBackend`.
+    ///
+    /// Events are only read from the real terminal when the backend is exactly
+    /// `CrosstermBackend<Stdout>` or `CrosstermBackend<Stderr>`. Any other backend,
+    /// including crossterm's writing to a `BufWriter` or a `File`, reads no events
+    /// unless it opts in with [`App::terminal_input`], and events can always be sent in
+    /// through [`App::input_sender`] instead.
+    pub fn from_terminal<B: Backend + 'static>(terminal: Terminal<B>, clock: u64) -> Self {
+        let redraw = Redraw::default();
+
+        let mut app = Self::with_boxed_terminal(Box::new(terminal), clock);
+        app.input.set_source(EventSource::for_backend::<B>());
+
+        app.states((
+            Chunks::default(),
+            Time::default(),
+            Events::default(),
+            CommandQueue::default(),
+            FixedTime::default(),
+            Tasks::new(redraw.handle()),
+            redraw,
+            Timers::default(),
+            Tweens::default(),
+            CurrentWidget(WidgetId::new()),
+        ))
+    }
 
-        Ok(Self {
+    fn with_boxed_terminal(terminal: Box<dyn AppTerminal>, clock: u64) -> Self {
+        Self {
             terminal,
-            widgets: vec![],
+            input: Input::new(EventSource::Empty),
+            widgets: BTreeMap::new(),
             states: HashMap::new(),
             clock: Duration::from_millis(clock),
+            terminal_config: None,
+            started: false,
+            unsorted: false,
+            set_orders: vec![],
+            transitions: vec![],
+            last_frame: None,
+            run_mode: RunMode::default(),
+            event_updates: vec![],
+            event_reader_retains: vec![],
+            sets: vec![],
+            set_names: HashSet::new(),
+            terminate: Arc::new(AtomicBool::new(false)),
+            suspend_requested: Arc::new(AtomicBool::new(false)),
+            #[cfg(unix)]
+            signals: vec![],
+            ctrl_c_exits: false,
+            ctrl_z_suspends: false,
+            command_error: None,
         }
-        .handle_panics()
-        .states((Chunks::default(), Time::default(), Events::default())))
     }
 
     /// Running this will ensure that any panic that happens, this will catch
     /// And prevent your terminal from messing up.
     pub fn handle_panics(self) -> Self {
         let original_hook = std::panic::take_hook();
+        let config = self.terminal_config.unwrap_or_default();
 
         std::panic::set_hook(Box::new(move |panic| {
-            reset_terminal().unwrap();
+            // A panicking task is handed back to the app as a failed task,
+            // so the app keeps running and still needs the terminal.
+            if !tasks::in_task() {
+                reset_terminal(&config).unwrap();
+            }
             original_hook(panic);
         }));
 
         self
     }
 
-    /// Adds the following Widgets to the system.
+    /// Stops the app, the same as a widget exiting, when the process receives
+    /// `SIGINT`, `SIGTERM` or `SIGHUP`, so the shutdown widgets still run,
+    /// and suspends the app when it receives `SIGTSTP`.
+    ///
+    /// Only apps that set the terminal up, such as with [`App::new`], handle signals,
+    /// and only while they hold the terminal. Otherwise the signals act as they normally would.
+    #[cfg(unix)]
+    fn register_signals(&mut self) -> io::Result<()> {
+        use signal_hook::{
+            consts::{SIGHUP, SIGINT, SIGTERM, SIGTSTP, SIGWINCH},
+            low_level::pipe,
+        };
+
+        if self.terminal_config.is_none() || !self.signals.is_empty() {
+            return Ok(());
+        }
+
+        let defaults = default_signal_actions()?;
+
+        for signal in [SIGINT, SIGTERM, SIGHUP] {
+            let id = signal_hook::flag::register(signal, self.terminate.clone())?;
+            self.signals.push(id);
+        }
+
+        let id = signal_hook::flag::register(SIGTSTP, self.suspend_requested.clone())?;
+        self.signals.push(id);
+
+        // Also wakes up the app if it is waiting for input,
+        // including on resizes so crossterm can report them.
+        for signal in [SIGINT, SIGTERM, SIGHUP, SIGTSTP, SIGWINCH] {
+            let id = pipe::register(signal, self.input.signal_pipe()?)?;
+            self.signals.push(id);
+        }
+
+        defaults.store(false, Ordering::Release);
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn register_signals(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Removes the signal actions added by [`App::register_signals`].
+    #[cfg(unix)]
+    fn unregister_signals(&mut self) {
+        if self.signals.is_empty() {
+            return;
+        }
+
+        for id in self.signals.drain(..) {
+            signal_hook::low_level::unregister(id);
+        }
+
+        if let Ok(defaults) = default_signal_actions() {
+            defaults.store(true, Ordering::Release);
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn unregister_signals(&mut self) {}
+
+    /// Sets whether pressing Ctrl-C stops the app, the same as a widget exiting,
+    /// so the shutdown widgets still run.
+    /// The terminal is in raw mode while the app runs, so Ctrl-C arrives as a key press
+    /// instead of `SIGINT`. This is on by default for apps created with [`App::new`].
+    pub fn ctrl_c_exits(mut self, exits: bool) -> Self {
+        self.ctrl_c_exits = exits;
+        self
+    }
+
+    /// Sets whether pressing Ctrl-Z suspends the app, handing the terminal back to the shell
+    /// until the app is brought back to the foreground.
+    /// This is on by default for apps created with [`App::new`] on unix.
+    pub fn ctrl_z_suspends(mut self, suspends: bool) -> Self {
+        self.ctrl_z_suspends = suspends && cfg!(unix);
+        self
+    }
+
+    /// Leaves raw mode and the alternate screen, runs `f` on the real terminal,
+    /// then sets the terminal back up and redraws everything on the next frame.
+    ///
+    /// Useful for running other programs, such as `$EDITOR`, from inside the app.
+    pub fn suspend<R>(&mut self, f: impl FnOnce(&States) -> R) -> io::Result<R> {
+        self.release_terminal()?;
+
+        let result = f(&self.states);
+
+        self.reacquire_terminal()?;
+
+        self.terminal.clear()?;
+        self.terminal.hide_cursor()?;
+
+        // Time spent suspended shouldn't count towards the next frame.
+        self.last_frame = None;
+        Res::<Redraw>::retrieve(&self.states).request_redraw();
+
+        Ok(result)
+    }
+
+    /// Prints the text above an inline viewport, where it stays in the terminal history.
+    /// Does nothing unless the app draws in an inline viewport, such as with [`App::new_inline`].
+    pub fn print_above(&mut self, text: impl Into<Text<'static>>) -> io::Result<()> {
+        let text = text.into();
+        let height = text.height() as u16;
+
+        self.terminal.insert_before(
+            height,
+            Box::new(move |buffer| Paragraph::new(text).render(buffer.area, buffer)),
+        )
+    }
+
+    /// Restores the terminal to how it was before the app set it up.
+    fn release_terminal(&mut self) -> io::Result<()> {
+        self.unregister_signals();
+
+        let Some(config) = self.terminal_config else {
+            return Ok(());
+        };
+
+        if config.inline_height().is_some() {
+            // Leave the cursor on the last line of the viewport, so the shell continues below it.
+            if let Some(frame) = self.states.get(&TypeId::of::<WidgetFrame>()) {
+                let area = frame
+                    .borrow()
+                    .downcast_ref::<WidgetFrame>()
+                    .map(|f| f.size());
+                if let Some(area) = area {
+                    execute!(stdout(), MoveTo(0, area.bottom().saturating_sub(1)))?;
+                }
+            }
+        } else {
+            self.terminal.show_cursor()?;
+        }
+
+        reset_terminal(&config)
+    }
+
+    /// Sets the terminal back up after [`App::release_terminal`].
+    fn reacquire_terminal(&mut self) -> io::Result<()> {
+        let Some(config) = self.terminal_config else {
+            return Ok(());
+        };
+
+        if config.inline_height().is_some() {
+            // The viewport is placed below wherever the cursor ended up,
+            // so a new terminal is needed.
+            self.terminal = Box::new(setup_terminal(&config)?);
+        } else {
+            resume_terminal(&config)?;
+        }
+
+        self.register_signals()
+    }
+
+    /// Stops the process until it is brought back to the foreground, like the shell expects
+    /// from Ctrl-Z, restoring the terminal while it is stopped.
+    #[cfg(unix)]
+    fn stop_process(&mut self) -> io::Result<()> {
+        // `SIGTSTP` is handled by the app, so the process is stopped with `SIGSTOP` instead,
+        // which returns once the process receives `SIGCONT`.
+        self.suspend(|_| signal_hook::low_level::raise(signal_hook::consts::SIGSTOP))?
+    }
+
+    #[cfg(not(unix))]
+    fn stop_process(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Adds the following Widgets to the [`Stage::Update`] stage of the system.
     /// This will take in a tuple of widgets, or a single widget.
-    pub fn widgets<I, T>(mut self, widget: impl IntoWidgetSet<I, T>) -> Self {
+    pub fn widgets<I, T>(self, widget: impl IntoWidgetSet<I, T>) -> Self {
+        self.add_widgets(Stage::Update, widget)
+    }
+
+    pub fn widget<W: Widget + 'static>(self, widget: W) -> Self {
+        self.add_widget(Stage::Update, widget)
+    }
+
+    /// Adds the following Widgets to the given stage of the system.
+    /// This will take in a tuple of widgets, or a single widget.
+    pub fn add_widgets<I, T>(mut self, stage: Stage, widget: impl IntoWidgetSet<I, T>) -> Self {
         for widget in widget.into_widget_set() {
-            self.widgets.push(widget);
+            self.push_widget(stage, widget);
+        }
+        self
+    }
+
+    /// Adds the following Widgets to the [`Stage::Shutdown`] stage of the system.
+    /// These run a single time once the app stops for any reason, before the terminal is restored,
+    /// making them the place to save anything that would otherwise be lost.
+    pub fn shutdown_widgets<I, T>(self, widget: impl IntoWidgetSet<I, T>) -> Self {
+        self.add_widgets(Stage::Shutdown, widget)
+    }
+
+    /// Adds the following Widgets to the [`Stage::Startup`] stage of the system.
+    /// These run a single time once the terminal is set up, before the first frame,
+    /// and any error they return will stop the app from running.
+    pub fn startup_widgets<I, T>(self, widget: impl IntoWidgetSet<I, T>) -> Self {
+        self.add_widgets(Stage::Startup, widget)
+    }
+
+    pub fn add_widget<W: Widget + 'static>(mut self, stage: Stage, widget: W) -> Self {
+        self.push_widget(stage, Box::new(widget));
+        self
+    }
+
+    /// Removes every widget labeled `L`.
+    pub fn remove_widgets<L: 'static>(mut self) -> Self {
+        let label = Label::of::<L>();
+
+        for entries in self.widgets.values_mut() {
+            entries.retain(|entry| !entry.order.labels.contains(&label));
+        }
+
+        self.forget_removed_readers();
+        self
+    }
+
+    /// Stops the widget with the given id from running, until it is enabled again.
+    pub fn disable_widget(mut self, id: WidgetId) -> Self {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.enabled = false;
+        }
+        self
+    }
+
+    /// Allows a disabled widget with the given id to run again.
+    pub fn enable_widget(mut self, id: WidgetId) -> Self {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.enabled = true;
         }
         self
     }
 
-    pub fn widget<W: Widget + 'static>(mut self, widget: W) -> Self {
-        self.widgets.push(Box::new(widget));
+    /// Replaces the widget with the given id, keeping its id, stage and ordering.
+    pub fn replace_widget<I, D, W: Widget + 'static>(
+        mut self,
+        id: WidgetId,
+        widget: impl IntoWidget<I, D, Widget = W>,
+    ) -> Self {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.widget = Box::new(widget.into_widget());
+        }
         self
     }
 
+    /// Removes the widget with the given id.
+    pub fn remove_widget(mut self, id: WidgetId) -> Self {
+        for entries in self.widgets.values_mut() {
+            entries.retain(|entry| entry.id != id);
+        }
+
+        self.forget_removed_readers();
+        self
+    }
+
+    /// Drops the place every removed widget was up to in each event type,
+    /// so the readers don't build up as widgets come and go.
+    fn forget_removed_readers(&mut self) {
+        let widgets = self
+            .widgets
+            .values()
+            .flatten()
+            .map(|entry| entry.id)
+            .chain(
+                self.transitions
+                    .iter()
+                    .flat_map(|transitions| transitions.widget_ids()),
+            )
+            .collect();
+
+        for retain in &self.event_reader_retains {
+            retain(&self.states, &widgets);
+        }
+    }
+
+    fn entry_mut(&mut self, id: WidgetId) -> Option<&mut WidgetEntry> {
+        self.widgets
+            .values_mut()
+            .flatten()
+            .find(|entry| entry.id == id)
+    }
+
+    /// Adds the widget to the stage, along with the ordering of any set registering it.
+    pub(crate) fn push_widget(&mut self, stage: Stage, widget: Box<dyn Widget>) {
+        let mut order = widget.order();
+
+        for set_order in &self.set_orders {
+            order.extend(set_order);
+        }
+
+        let id = widget.id().unwrap_or_else(WidgetId::new);
+
+        self.widgets.entry(stage).or_default().push(WidgetEntry {
+            widget,
+            order,
+            id,
+            enabled: true,
+        });
+
+        self.unsorted = true;
+    }
+
     /// Add the following states to the system
     /// This will take in a state or a tuple of states.
     pub fn states<S: MultiFromStates>(self, state: S) -> Self {
         state.insert_states(self)
     }
 
+    /// Returns the given state, such as from [`Set::finish`] or [`Set::cleanup`].
+    ///
+    /// # Panics
+    /// Panics if the state has not been registered.
+    pub fn state<T: 'static>(&self) -> Res<'_, T> {
+        Res::<T>::retrieve(&self.states)
+    }
+
+    /// Returns the given state mutably.
+    ///
+    /// # Panics
+    /// Panics if the state has not been registered.
+    pub fn state_mut<T: 'static>(&self) -> ResMut<'_, T> {
+        ResMut::<T>::retrieve(&self.states)
+    }
+
+    /// Registers the event type `E`, so widgets can send it with
+    /// [`EventWriter<E>`](crate::EventWriter) and read it with [`EventReader<E>`](crate::EventReader).
+    pub fn add_event<E: 'static>(mut self) -> Self {
+        if !self.states.contains_key(&TypeId::of::<EventQueue<E>>()) {
+            self.event_updates
+                .push(|states| ResMut::<EventQueue<E>>::retrieve(states).update());
+            self.event_reader_retains.push(|states, widgets| {
+                ResMut::<EventQueue<E>>::retrieve(states).retain_readers(widgets)
+            });
+            self = self.states(EventQueue::<E>::default());
+        }
+        self
+    }
+
+    /// Returns a sender that other threads can use to push events of type `T` into the app,
+    /// which widgets read through [`UserEvents<T>`].
+    pub fn event_sender<T: Send + 'static>(&mut self) -> EventSender<T> {
+        if !self.states.contains_key(&TypeId::of::<UserEvents<T>>()) {
+            let redraw = Res::<Redraw>::retrieve(&self.states).handle();
+
+            self.states.insert(
+                TypeId::of::<UserEvents<T>>(),
+                RefCell::new(Box::new(UserEvents::<T>::new(redraw))),
+            );
+            self.event_updates
+                .push(|states| ResMut::<UserEvents<T>>::retrieve(states).receive());
+        }
+
+        Res::<UserEvents<T>>::retrieve(&self.states).sender()
+    }
+
+    /// Reads events from the real terminal through crossterm, whatever the backend draws to,
+    /// such as a `CrosstermBackend<BufWriter<Stdout>>`.
+    pub fn terminal_input(mut self) -> Self {
+        self.input.set_source(EventSource::Terminal);
+        self
+    }
+
+    /// Returns a sender that feeds events into [`Events`] in place of the real terminal,
+    /// allowing the app to be driven by other threads, scripts or tests.
+    pub fn input_sender(&mut self) -> EventSender<Event> {
+        let redraw = Res::<Redraw>::retrieve(&self.states).handle();
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        self.input.set_source(EventSource::Channel(receiver));
+
+        EventSender::new(sender, redraw)
+    }
+
+    /// Sets how often the [`Stage::FixedUpdate`] widgets run.
+    pub fn fixed_timestep(self, timestep: Duration) -> Self {
+        self.states(FixedTime::new(timestep))
+    }
+
+    /// Sets when the app runs a frame, see [`RunMode`] for the options.
+    pub fn run_mode(mut self, run_mode: RunMode) -> Self {
+        self.run_mode = run_mode;
+        self
+    }
+
+    /// Adds an app level state machine, starting in the given state.
+    ///
+    /// The current state is available as [`AppState<S>`](crate::app_state::AppState),
+    /// and transitions are requested through [`NextState<S>`](crate::app_state::NextState).
+    pub fn app_state<S: Clone + Eq + Hash + 'static>(mut self, initial: S) -> Self {
+        self.transitions.push(Box::new(StateMachine::<S>::new()));
+        self.states(StateMachine::states(initial))
+    }
+
+    /// Adds the following Widgets to run a single time whenever the state machine enters `state`.
+    pub fn on_enter<S: Clone + Eq + Hash + 'static, I, T>(
+        mut self,
+        state: S,
+        widget: impl IntoWidgetSet<I, T>,
+    ) -> Self {
+        self.state_machine::<S>()
+            .add_on_enter(state, widget.into_widget_set());
+        self
+    }
+
+    /// Adds the following Widgets to run a single time whenever the state machine exits `state`.
+    pub fn on_exit<S: Clone + Eq + Hash + 'static, I, T>(
+        mut self,
+        state: S,
+        widget: impl IntoWidgetSet<I, T>,
+    ) -> Self {
+        self.state_machine::<S>()
+            .add_on_exit(state, widget.into_widget_set());
+        self
+    }
+
+    fn state_machine<S: Clone + Eq + Hash + 'static>(&mut self) -> &mut StateMachine<S> {
+        self.transitions
+            .iter_mut()
+            .find_map(|transitions| transitions.as_any_mut().downcast_mut())
+            .unwrap_or_else(|| {
+                panic!(
+                    "State machine `{}` Not Found, add it with `App::app_state` first",
+                    type_name::<S>()
+                )
+            })
+    }
+
     /// Add a set to the system
     pub fn sets(self, set: impl Sets) -> Self {
         set.register_sets(self)
     }
 
+    /// Registers a single set, along with any dependencies it has,
+    /// unless a unique set with the same name was already added.
+    pub(crate) fn register_set<S: Set + 'static>(mut self, set: S) -> Self {
+        if set.is_unique() && !self.set_names.insert(set.name().to_string()) {
+            return self;
+        }
+
+        let mut app = set.dependencies().register(self);
+        app = app.register_set_widgets(&set);
+
+        // Sets added once the app has started have missed the point where every set is finished.
+        if app.started {
+            app = set.finish(app);
+        }
+
+        app.sets.push(Box::new(set));
+        app
+    }
+
+    /// Runs the set, labeling every widget it adds with the type of the set.
+    pub(crate) fn register_set_widgets<S: Set + 'static>(self, set: &S) -> Self {
+        let order = WidgetOrder {
+            labels: vec![Label::of::<S>()],
+            ..Default::default()
+        };
+
+        self.with_set_order(order, |app| set.register_set(app))
+    }
+
+    /// Applies the given order to every widget added within `register`.
+    pub(crate) fn with_set_order(
+        mut self,
+        order: WidgetOrder,
+        register: impl FnOnce(Self) -> Self,
+    ) -> Self {
+        self.set_orders.push(order);
+        let mut app = register(self);
+        app.set_orders.pop();
+        app
+    }
+
     /// Run the app, returning an error if any of the functions error out.
-    pub fn run(mut self) -> WidgetResult {
+    pub fn run(self) -> WidgetResult {
+        self.run_returning::<()>().map(|_| ())
+    }
+
+    /// Run the app, returning the value given to [`Events::exit_with`]
+    /// along with the final states, once the terminal has been restored.
+    pub fn run_returning<T: 'static>(mut self) -> Result<AppExit<T>, WidgetError> {
         let result = self.inner_run();
 
-        restore_terminal(self.terminal)?;
+        // An error from the loop is more useful than one from shutting down, so it takes priority.
+        let result = result.and(self.shutdown());
 
-        result
+        self.cleanup();
+
+        self.release_terminal()?;
+
+        result?;
+
+        Ok(AppExit {
+            value: self.take_exit_value(),
+            states: self.states,
+        })
     }
 
     fn inner_run(&mut self) -> WidgetResult {
         self.terminal.hide_cursor()?;
 
+        self.startup()?;
+
+        let redraw = Res::<Redraw>::retrieve(&self.states).clone();
+        self.input.start(&redraw)?;
+        self.register_signals()?;
+
         loop {
-            self.terminal.autoresize()?;
-            let mut frame = self.terminal.get_frame();
+            let first = self.next_event()?;
 
-            let widget_frame = WidgetFrame {
-                cursor_position: None,
-                buffer: frame.buffer_mut().clone(),
-                viewport_area: frame.area(),
-                count: frame.count(),
-            };
+            // Drain anything else already waiting, so bursts of input land in a single frame.
+            // Capped, so a flood of events such as mouse moves can't hold the frame back forever,
+            // with the rest left for the next frame.
+            let mut events = Vec::from_iter(first);
+            while events.len() < MAX_EVENTS_PER_FRAME {
+                match self.input.try_next()? {
+                    Some(event) => events.push(event),
+                    None => break,
+                }
+            }
 
-            self.states.insert(
-                TypeId::of::<WidgetFrame>(),
-                RefCell::new(Box::new(widget_frame)),
-            );
+            if self.ctrl_c_exits && take_ctrl_key(&mut events, 'c') {
+                self.terminate.store(true, Ordering::Release);
+            }
+
+            if self.terminate.load(Ordering::Acquire) {
+                return Ok(());
+            }
+
+            if self.ctrl_z_suspends && take_ctrl_key(&mut events, 'z') {
+                self.suspend_requested.store(true, Ordering::Release);
+            }
+
+            if self.suspend_requested.swap(false, Ordering::AcqRel) {
+                self.stop_process()?;
+            }
+
+            let now = Instant::now();
+            let delta = self
+                .last_frame
+                .map_or(Duration::ZERO, |last_frame| now - last_frame);
+            self.last_frame = Some(now);
+
+            Res::<Redraw>::retrieve(&self.states).clear_passed_deadline(now);
+
+            self.frame(events, delta)?;
+
+            // Handle App Events
+            if self.exit_requested() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Waits for the next event, returning `None` when it is time for a frame without one.
+    ///
+    /// Continuous apps wait for at most the clock, while reactive apps sleep until
+    /// a redraw is requested or the nearest timer, fixed step or delayed redraw is due.
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        let redraw = Res::<Redraw>::retrieve(&self.states).clone();
 
+        let deadline = match self.run_mode {
+            RunMode::Continuous => Some(Instant::now() + self.clock),
+            RunMode::Reactive => self.next_wakeup(),
+        };
+
+        loop {
+            if self.run_mode == RunMode::Reactive && redraw.take() {
+                return Ok(None);
+            }
+
+            if self.terminate.load(Ordering::Acquire)
+                || self.suspend_requested.load(Ordering::Acquire)
             {
-                let mut chunks = ResMut::<Chunks>::retrieve(&self.states);
+                return Ok(None);
+            }
+
+            let now = Instant::now();
+            if deadline.is_some_and(|deadline| deadline <= now) {
+                return Ok(None);
+            }
+
+            let timeout = deadline.map(|deadline| deadline - now);
+            if let Some(event) = self.input.wait(timeout)? {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    /// Returns when the next frame is due without any input,
+    /// or `None` if nothing is waiting on time passing.
+    fn next_wakeup(&self) -> Option<Instant> {
+        let now = Instant::now();
+        let redraw = Res::<Redraw>::retrieve(&self.states).deadline();
+        let timer = Res::<Timers>::retrieve(&self.states)
+            .next_finish()
+            .map(|remaining| now + remaining);
+
+        let fixed_step = self
+            .widgets
+            .get(&Stage::FixedUpdate)
+            .is_some_and(|widgets| !widgets.is_empty())
+            .then(|| Res::<FixedTime>::retrieve(&self.states).until_next_step())
+            .flatten()
+            .map(|remaining| now + remaining);
+
+        // A requested transition happens at the start of the next frame.
+        let transition = self
+            .transitions
+            .iter()
+            .any(|transitions| transitions.pending(&self.states))
+            .then_some(now);
+
+        [redraw, timer, fixed_step, transition]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// Runs the [`Stage::Startup`] widgets, if they haven't been run yet.
+    pub(crate) fn startup(&mut self) -> WidgetResult {
+        if self.started {
+            return Ok(());
+        }
+        self.started = true;
 
-                chunks.clear();
+        self.insert_frame()?;
 
-                let mut events = ResMut::<Events>::retrieve(&self.states);
+        // Any set added while finishing is finished straight away, as the app has started.
+        let sets = std::mem::take(&mut self.sets);
+        self.with_app(|mut app| {
+            for set in &sets {
+                app = set.finish(app);
+            }
+
+            let added = std::mem::replace(&mut app.sets, sets);
+            app.sets.extend(added);
+            app
+        });
 
-                let mut time = ResMut::<Time>::retrieve(&self.states);
+        self.run_stage(Stage::Startup)?;
 
-                events.event = None;
+        for transitions in &mut self.transitions {
+            transitions.enter_initial(&mut self.states)?;
+        }
 
-                let start_time = SystemTime::now();
+        self.apply_commands()?;
+
+        Ok(())
+    }
 
-                if crossterm::event::poll(self.clock)? {
-                    events.event = Some(crossterm::event::read()?);
+    /// Applies every queued command, in the order they were queued,
+    /// returning the first error any of them reported.
+    fn apply_commands(&mut self) -> WidgetResult {
+        let commands = ResMut::<CommandQueue>::retrieve(&self.states).take();
+
+        if !commands.is_empty() {
+            self.with_app(|mut app| {
+                for command in commands {
+                    app = command(app);
                 }
+                app
+            });
+        }
+
+        match self.command_error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports an error from a command, which is returned once the commands have been applied.
+    pub(crate) fn report_error(&mut self, error: WidgetError) {
+        self.command_error.get_or_insert(error);
+    }
 
-                let total_time = SystemTime::now().duration_since(start_time).unwrap();
+    /// Runs a change that takes the app by value, swapping the app out while it runs.
+    fn with_app(&mut self, change: impl FnOnce(Self) -> Self) {
+        let detached = Self::with_boxed_terminal(Box::new(DetachedTerminal), 0);
+        let app = std::mem::replace(self, detached);
+
+        *self = change(app);
+    }
 
-                time.set_duration(total_time);
+    /// Runs the [`Stage::Shutdown`] widgets.
+    pub(crate) fn shutdown(&mut self) -> WidgetResult {
+        self.run_stage(Stage::Shutdown)
+    }
+
+    /// Runs the cleanup of every set, in the reverse of the order they were added.
+    pub(crate) fn cleanup(&mut self) {
+        let sets = std::mem::take(&mut self.sets);
+
+        self.with_app(|mut app| {
+            for set in sets.iter().rev() {
+                app = set.cleanup(app);
             }
+            app
+        });
+    }
 
-            for widget in &mut self.widgets {
-                widget.call(&mut self.states)?;
+    /// Creates a new frame for the widgets to draw to.
+    fn insert_frame(&mut self) -> WidgetResult {
+        let widget_frame = self.terminal.begin_frame()?;
+
+        self.states.insert(
+            TypeId::of::<WidgetFrame>(),
+            RefCell::new(Box::new(widget_frame)),
+        );
+
+        Ok(())
+    }
+
+    /// Sorts the widgets of every stage to meet their ordering constraints,
+    /// if any were added since the last sort.
+    fn sort_widgets(&mut self) -> WidgetResult {
+        if !self.unsorted {
+            return Ok(());
+        }
+        self.unsorted = false;
+
+        for entries in self.widgets.values_mut() {
+            *entries = sort_by_order(std::mem::take(entries), |entry| &entry.order)?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs every widget in the stage, then applies any commands they queued.
+    fn run_stage(&mut self, stage: Stage) -> WidgetResult {
+        self.sort_widgets()?;
+
+        for entry in self.widgets.get_mut(&stage).into_iter().flatten() {
+            if entry.enabled {
+                ResMut::<CurrentWidget>::retrieve(&self.states).0 = entry.id;
+                entry.widget.call(&mut self.states)?;
             }
+        }
 
-            // Update the window.
-            {
-                let widget_frame = Res::<WidgetFrame>::retrieve(&self.states);
+        self.apply_commands()?;
 
-                if let Some((x, y)) = widget_frame.cursor_position {
-                    frame.set_cursor_position((x, y));
-                }
+        Ok(())
+    }
+
+    /// Runs a single frame of the app, passing the given events to the widgets.
+    /// `delta` is the real time since the last frame, which drives [`Time`] and the fixed timestep.
+    pub(crate) fn frame(&mut self, events: Vec<Event>, delta: Duration) -> WidgetResult {
+        self.insert_frame()?;
+
+        {
+            let mut chunks = ResMut::<Chunks>::retrieve(&self.states);
+
+            chunks.clear();
 
-                *frame.buffer_mut() = widget_frame.buffer.clone();
+            let mut time = ResMut::<Time>::retrieve(&self.states);
+
+            ResMut::<Events>::retrieve(&self.states).set_events(events);
+
+            for update in &self.event_updates {
+                update(&self.states);
             }
 
-            // Render Frame
-            self.terminal.flush()?;
+            time.tick(delta, Res::<WidgetFrame>::retrieve(&self.states).count());
 
-            self.terminal.swap_buffers();
+            ResMut::<Timers>::retrieve(&self.states).tick(delta);
 
-            self.terminal.backend_mut().flush()?;
+            ResMut::<Tweens>::retrieve(&self.states).tick(delta);
+        }
 
-            // Handle App Events
-            if ResMut::<Events>::retrieve(&self.states).exit {
-                return Ok(());
+        let completed = ResMut::<Tasks>::retrieve(&self.states).take_completed();
+        for complete in completed {
+            complete(&self.states);
+        }
+
+        for transitions in &mut self.transitions {
+            transitions.apply(&mut self.states)?;
+        }
+
+        self.apply_commands()?;
+
+        for stage in Stage::FRAME {
+            if stage == Stage::FixedUpdate {
+                let steps = ResMut::<FixedTime>::retrieve(&self.states).accumulate(delta);
+
+                for _ in 0..steps {
+                    self.run_stage(stage)?;
+                }
+            } else {
+                self.run_stage(stage)?;
             }
         }
+
+        // Render Frame
+        self.terminal
+            .end_frame(&Res::<WidgetFrame>::retrieve(&self.states))?;
+
+        // Keep drawing every clock tick while anything is moving, even when the app is reactive,
+        // including tweens started during this frame.
+        if Res::<Tweens>::retrieve(&self.states).animating() {
+            Res::<Redraw>::retrieve(&self.states).request_redraw_in(self.clock);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the backend the app draws to, if it is a `B`.
+    pub(crate) fn backend<B: Backend + 'static>(&self) -> Option<&B> {
+        self.terminal
+            .as_any()
+            .downcast_ref::<Terminal<B>>()
+            .map(Terminal::backend)
+    }
+
+    /// Takes the value the app exited with, if it is a `T`.
+    pub(crate) fn take_exit_value<T: 'static>(&mut self) -> Option<T> {
+        ResMut::<Events>::retrieve(&self.states).take_exit_value()
+    }
+
+    /// Returns whether a widget has registered an exit.
+    pub(crate) fn exit_requested(&self) -> bool {
+        Res::<Events>::retrieve(&self.states).exit
+    }
+}
+
+/// Removes every event for Ctrl and the given key, returning whether it was pressed.
+fn take_ctrl_key(events: &mut Vec<Event>, key: char) -> bool {
+    let mut pressed = false;
+
+    events.retain(|event| match event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(code),
+            modifiers,
+            kind,
+            ..
+        }) if *code == key && *modifiers == KeyModifiers::CONTROL => {
+            pressed |= *kind == KeyEventKind::Press;
+            false
+        }
+        _ => true,
+    });
+
+    pressed
+}
+
+/// Returns the flag that makes the signals apps handle act as they normally would,
+/// which is set whenever no app is handling them.
+///
+/// signal-hook keeps its own handler installed once every action has been unregistered,
+/// which would otherwise leave the signals ignored after the app stops.
+#[cfg(unix)]
+fn default_signal_actions() -> io::Result<Arc<AtomicBool>> {
+    use std::sync::Mutex;
+
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM, SIGTSTP};
+
+    static DEFAULTS: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+    let mut defaults = DEFAULTS.lock().unwrap();
+    if let Some(defaults) = &*defaults {
+        return Ok(defaults.clone());
+    }
+
+    let flag = Arc::new(AtomicBool::new(true));
+    for signal in [SIGHUP, SIGINT, SIGTERM, SIGTSTP] {
+        signal_hook::flag::register_conditional_default(signal, flag.clone())?;
     }
+
+    Ok(defaults.insert(flag).clone())
 }
@@ -0,0 +1,273 @@
+use std::{any::Any, collections::HashMap, hash::Hash};
+
+use crate::{
+    event_bus::CurrentWidget,
+    states::States,
+    widget::{condition::Condition, id::WidgetId, Widget},
+    Res, ResMut, State, WidgetParam, WidgetResult,
+};
+
+/// The current state of an app level state machine, such as the screen being shown.
+///
+/// Registered with [`App::app_state`](crate::App::app_state), and changed by setting [`NextState`].
+pub struct AppState<S>(S);
+
+impl<S: 'static> State for AppState<S> {}
+
+impl<S> AppState<S> {
+    /// Returns the current state.
+    pub fn get(&self) -> &S {
+        &self.0
+    }
+}
+
+/// Requests a transition of the state machine, which happens at the start of the next frame.
+pub struct NextState<S>(Option<S>);
+
+impl<S: 'static> State for NextState<S> {}
+
+impl<S> NextState<S> {
+    /// Requests that the state machine moves into the given state.
+    pub fn set(&mut self, state: S) {
+        self.0 = Some(state);
+    }
+}
+
+/// Passes while the state machine is in the given state.
+/// Used with [`IntoWidget::run_if`](crate::IntoWidget::run_if) for widgets that only run in that state.
+pub fn in_state<S: Eq + 'static>(state: S) -> InState<S> {
+    InState(state)
+}
+
+/// The condition created by [`in_state`].
+pub struct InState<S>(S);
+
+impl<S: Eq + 'static> Condition for InState<S> {
+    fn check(&mut self, states: &States) -> bool {
+        *Res::<AppState<S>>::retrieve(states).get() == self.0
+    }
+}
+
+/// Runs the transitions of a state machine, no matter what its state type is.
+pub(crate) trait Transitions {
+    /// Runs the enter widgets of the initial state.
+    fn enter_initial(&mut self, states: &mut States) -> WidgetResult;
+
+    /// Moves into the next state if one was requested, running the exit and enter widgets.
+    fn apply(&mut self, states: &mut States) -> WidgetResult;
+
+    /// Returns whether a transition was requested, but hasn't happened yet.
+    fn pending(&self, states: &States) -> bool;
+
+    /// Returns the ids of every enter and exit widget.
+    fn widget_ids(&self) -> Vec<WidgetId>;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+type HookWidgets = Vec<(WidgetId, Box<dyn Widget>)>;
+
+/// The enter and exit widgets of a single state machine.
+pub(crate) struct StateMachine<S> {
+    on_enter: HashMap<S, HookWidgets>,
+    on_exit: HashMap<S, HookWidgets>,
+}
+
+impl<S: Clone + Eq + Hash + 'static> StateMachine<S> {
+    pub(crate) fn new() -> Self {
+        Self {
+            on_enter: HashMap::new(),
+            on_exit: HashMap::new(),
+        }
+    }
+
+    /// Creates the states that hold the current and next state of the machine.
+    pub(crate) fn states(initial: S) -> (AppState<S>, NextState<S>) {
+        (AppState(initial), NextState(None))
+    }
+
+    pub(crate) fn add_on_enter(&mut self, state: S, widgets: Vec<Box<dyn Widget>>) {
+        self.on_enter
+            .entry(state)
+            .or_default()
+            .extend(widgets.into_iter().map(with_id));
+    }
+
+    pub(crate) fn add_on_exit(&mut self, state: S, widgets: Vec<Box<dyn Widget>>) {
+        self.on_exit
+            .entry(state)
+            .or_default()
+            .extend(widgets.into_iter().map(with_id));
+    }
+}
+
+fn with_id(widget: Box<dyn Widget>) -> (WidgetId, Box<dyn Widget>) {
+    (widget.id().unwrap_or_else(WidgetId::new), widget)
+}
+
+fn run_all(widgets: Option<&mut HookWidgets>, states: &mut States) -> WidgetResult {
+    for (id, widget) in widgets.into_iter().flatten() {
+        ResMut::<CurrentWidget>::retrieve(states).0 = *id;
+        widget.call(states)?;
+    }
+
+    Ok(())
+}
+
+impl<S: Clone + Eq + Hash + 'static> Transitions for StateMachine<S> {
+    fn enter_initial(&mut self, states: &mut States) -> WidgetResult {
+        let initial = Res::<AppState<S>>::retrieve(states).get().clone();
+
+        run_all(self.on_enter.get_mut(&initial), states)
+    }
+
+    fn apply(&mut self, states: &mut States) -> WidgetResult {
+        let Some(next) = ResMut::<NextState<S>>::retrieve(states).0.take() else {
+            return Ok(());
+        };
+
+        let previous = {
+            let mut current = ResMut::<AppState<S>>::retrieve(states);
+
+            if current.0 == next {
+                return Ok(());
+            }
+
+            std::mem::replace(&mut current.0, next.clone())
+        };
+
+        run_all(self.on_exit.get_mut(&previous), states)?;
+        run_all(self.on_enter.get_mut(&next), states)
+    }
+
+    fn pending(&self, states: &States) -> bool {
+        Res::<NextState<S>>::retrieve(states).0.is_some()
+    }
+
+    fn widget_ids(&self) -> Vec<WidgetId> {
+        self.on_enter
+            .values()
+            .chain(self.on_exit.values())
+            .flatten()
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::KeyCode;
+
+    use crate::{
+        app_state::*,
+        testing::{AppHarness, Log},
+        *,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Screen {
+        Menu,
+        Game,
+    }
+
+    fn enter_menu(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("enter menu");
+        Ok(())
+    }
+
+    fn exit_menu(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("exit menu");
+        Ok(())
+    }
+
+    fn enter_game(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("enter game");
+        Ok(())
+    }
+
+    fn menu(
+        events: Res<Events>,
+        mut log: ResMut<Log>,
+        mut next: ResMut<NextState<Screen>>,
+    ) -> WidgetResult {
+        log.push("menu");
+        if events.key(KeyCode::Enter) {
+            next.set(Screen::Game);
+        }
+        Ok(())
+    }
+
+    fn game(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("game");
+        Ok(())
+    }
+
+    #[test]
+    fn transitions_run_hooks_once() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .app_state(Screen::Menu)
+                .on_enter(Screen::Menu, enter_menu)
+                .on_exit(Screen::Menu, exit_menu)
+                .on_enter(Screen::Game, enter_game)
+                .widgets((
+                    menu.run_if(in_state(Screen::Menu)),
+                    game.run_if(in_state(Screen::Game)),
+                ))
+        });
+
+        harness.step().unwrap();
+        harness.push_key(KeyCode::Enter);
+        harness.step_n(3).unwrap();
+
+        assert_eq!(
+            harness.log().entries(),
+            [
+                "enter menu",
+                "menu",
+                "menu",
+                "exit menu",
+                "enter game",
+                "game",
+                "game"
+            ]
+        );
+        assert_eq!(*harness.state::<AppState<Screen>>().get(), Screen::Game);
+    }
+
+    fn start_game(mut next: ResMut<NextState<Screen>>) -> WidgetResult {
+        next.set(Screen::Game);
+        Ok(())
+    }
+
+    fn quit_game(mut events: ResMut<Events>) -> WidgetResult {
+        events.register_exit();
+        Ok(())
+    }
+
+    #[test]
+    fn reactive_apps_wake_up_for_transitions() {
+        let terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(1, 1)).unwrap();
+        let mut app = App::from_terminal(terminal, 0)
+            .run_mode(RunMode::Reactive)
+            .app_state(Screen::Menu)
+            .on_enter(Screen::Game, quit_game)
+            .widgets(start_game.run_if(in_state(Screen::Menu)));
+
+        // Wakes the app up late if the transition doesn't, so a failure doesn't hang.
+        let late = app.event_sender::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            let _ = late.send(());
+        });
+
+        let started = std::time::Instant::now();
+        app.run().unwrap();
+
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,319 @@
+use std::{any::TypeId, process};
+
+use ratatui::text::Text;
+
+use crate::{
+    set::Sets,
+    stage::Stage,
+    states::{MultiFromStates, States},
+    widget::{id::WidgetId, into_widget::IntoWidget, into_widget_set::IntoWidgetSet, Widget},
+    App, Events, ResMut, State, WidgetParam, WidgetResult,
+};
+
+/// A change to the app, which can only be made once no widgets are running.
+type Command = Box<dyn FnOnce(App) -> App>;
+
+/// The state holding every command that is waiting to be applied.
+#[derive(Default, State)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+}
+
+impl CommandQueue {
+    pub(crate) fn take(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.commands)
+    }
+}
+
+/// Queues structural changes to the app, such as adding states, widgets or sets.
+///
+/// Commands are applied in the order they were queued, once the current stage finishes,
+/// so any state or widget they add is available from the next stage onwards.
+pub struct Commands<'a> {
+    queue: ResMut<'a, CommandQueue>,
+}
+
+impl<'a> WidgetParam for Commands<'a> {
+    type Item<'new> = Commands<'new>;
+
+    fn retrieve(resources: &States) -> Self::Item<'_> {
+        Commands {
+            queue: ResMut::<CommandQueue>::retrieve(resources),
+        }
+    }
+}
+
+impl Commands<'_> {
+    /// Queues a change to the app, with full access to its builder methods.
+    pub fn add(&mut self, command: impl FnOnce(App) -> App + 'static) {
+        self.queue.commands.push(Box::new(command));
+    }
+
+    /// Inserts the following states, replacing any that already exist.
+    /// This will take in a state or a tuple of states.
+    pub fn insert_states<S: MultiFromStates + 'static>(&mut self, state: S) {
+        self.add(move |app| app.states(state));
+    }
+
+    /// Removes the state `S` from the app.
+    pub fn remove_state<S: 'static>(&mut self) {
+        self.add(|mut app| {
+            app.states.remove(&TypeId::of::<S>());
+            app
+        });
+    }
+
+    /// Adds the following Widgets to the [`Stage::Update`] stage of the app.
+    pub fn add_widgets<I, T>(&mut self, widget: impl IntoWidgetSet<I, T>) {
+        self.add_stage_widgets(Stage::Update, widget);
+    }
+
+    /// Adds the following Widgets to the given stage of the app.
+    /// Widgets added to [`Stage::Startup`] once the app has started will never run.
+    pub fn add_stage_widgets<I, T>(&mut self, stage: Stage, widget: impl IntoWidgetSet<I, T>) {
+        let widgets = widget.into_widget_set();
+
+        self.add(move |mut app| {
+            for widget in widgets {
+                app.push_widget(stage, widget);
+            }
+            app
+        });
+    }
+
+    /// Removes every widget labeled `L`.
+    pub fn remove_widgets<L: 'static>(&mut self) {
+        self.add(|app| app.remove_widgets::<L>());
+    }
+
+    /// Stops the widget with the given id from running, until it is enabled again.
+    pub fn disable_widget(&mut self, id: WidgetId) {
+        self.add(move |app| app.disable_widget(id));
+    }
+
+    /// Allows a disabled widget with the given id to run again.
+    pub fn enable_widget(&mut self, id: WidgetId) {
+        self.add(move |app| app.enable_widget(id));
+    }
+
+    /// Replaces the widget with the given id, keeping its id, stage and ordering.
+    pub fn replace_widget<I, D, W: Widget + 'static>(
+        &mut self,
+        id: WidgetId,
+        widget: impl IntoWidget<I, D, Widget = W>,
+    ) {
+        let widget = widget.into_widget();
+        self.add(move |app| app.replace_widget(id, widget));
+    }
+
+    /// Removes the widget with the given id.
+    pub fn remove_widget(&mut self, id: WidgetId) {
+        self.add(move |app| app.remove_widget(id));
+    }
+
+    /// Adds the following sets to the app.
+    pub fn sets(&mut self, set: impl Sets + 'static) {
+        self.add(move |app| app.sets(set));
+    }
+
+    /// Suspends the app, running `f` on the real terminal, the same as [`App::suspend`].
+    /// Any error `f` returns stops the app.
+    pub fn suspend(&mut self, f: impl FnOnce(&States) -> WidgetResult + 'static) {
+        self.add(|mut app| {
+            match app.suspend(f) {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => app.report_error(error),
+                Err(error) => app.report_error(error.into()),
+            }
+            app
+        });
+    }
+
+    /// Suspends the app to run the given program, such as `$EDITOR`,
+    /// waiting for it to finish before the app continues.
+    pub fn run_program(&mut self, mut program: process::Command) {
+        self.suspend(move |_| {
+            program.status()?;
+            Ok(())
+        });
+    }
+
+    /// Prints the text above the inline viewport, the same as [`App::print_above`].
+    pub fn print_above(&mut self, text: impl Into<Text<'static>>) {
+        let text = text.into();
+
+        self.add(|mut app| {
+            if let Err(error) = app.print_above(text) {
+                app.report_error(error.into());
+            }
+            app
+        });
+    }
+
+    /// Stops the app once the current frame finishes.
+    pub fn exit(&mut self) {
+        self.add(|app| {
+            ResMut::<Events>::retrieve(&app.states).register_exit();
+            app
+        });
+    }
+
+    /// Stops the app once the current frame finishes, returning the given value from
+    /// [`App::run_returning`].
+    pub fn exit_with<T: 'static>(&mut self, value: T) {
+        self.add(|app| {
+            ResMut::<Events>::retrieve(&app.states).exit_with(value);
+            app
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        testing::{AppHarness, Log},
+        *,
+    };
+
+    use super::Commands;
+
+    struct Plugin;
+
+    fn spawn(mut commands: Commands, mut log: ResMut<Log>) -> WidgetResult {
+        log.push("spawn");
+        commands.add_widgets(plugin.label::<Plugin>());
+        commands.remove_widgets::<Spawner>();
+        Ok(())
+    }
+
+    fn plugin(mut commands: Commands, mut log: ResMut<Log>) -> WidgetResult {
+        log.push("plugin");
+        commands.remove_widgets::<Plugin>();
+        commands.exit();
+        Ok(())
+    }
+
+    struct Spawner;
+
+    #[test]
+    fn widgets_change_while_running() {
+        let mut harness = AppHarness::new(1, 1)
+            .app(|app| app.states(Log::default()).widgets(spawn.label::<Spawner>()));
+
+        harness.step_n(3).unwrap();
+
+        assert_eq!(harness.log().entries(), ["spawn", "plugin"]);
+        assert!(harness.exited());
+    }
+
+    #[derive(State)]
+    struct Inserted(u32);
+
+    fn insert(mut commands: Commands) -> WidgetResult {
+        commands.insert_states(Inserted(7));
+        Ok(())
+    }
+
+    fn read(inserted: Res<Inserted>, mut log: ResMut<Log>) -> WidgetResult {
+        if inserted.0 == 7 {
+            log.push("read");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn states_are_available_next_stage() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .add_widgets(Stage::PreUpdate, insert)
+                .widgets(read)
+        });
+
+        harness.step().unwrap();
+
+        assert_eq!(harness.log().entries(), ["read"]);
+    }
+
+    #[derive(Default, State)]
+    struct Edited(bool);
+
+    fn edit(events: Res<Events>, mut commands: Commands) -> WidgetResult {
+        if events.key(crossterm::event::KeyCode::Char('e')) {
+            commands.suspend(|states| {
+                ResMut::<Edited>::retrieve(states).0 = true;
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn suspending_runs_outside_the_frame() {
+        let mut harness =
+            AppHarness::new(1, 1).app(|app| app.states(Edited::default()).widgets(edit));
+
+        harness.step().unwrap();
+
+        // Clears the redraw every app starts with, so only one from suspending is left.
+        harness.state::<Redraw>().take();
+        harness.step().unwrap();
+        assert!(!harness.state::<Redraw>().take());
+
+        harness.push_key(crossterm::event::KeyCode::Char('e'));
+        harness.step().unwrap();
+
+        assert!(harness.state::<Edited>().0);
+        assert!(harness.state::<Redraw>().take());
+    }
+
+    fn broken_editor(mut commands: Commands) -> WidgetResult {
+        commands.run_program(std::process::Command::new("/nonexistent/editor"));
+        Ok(())
+    }
+
+    #[test]
+    fn command_errors_stop_the_app() {
+        let mut harness = AppHarness::new(1, 1).app(|app| app.widgets(broken_editor));
+
+        assert!(harness.step().is_err());
+    }
+
+    fn log_line(mut commands: Commands) -> WidgetResult {
+        commands.print_above("Downloaded 1 file");
+        Ok(())
+    }
+
+    #[test]
+    fn inline_apps_print_above_the_viewport() {
+        let terminal = ratatui::Terminal::with_options(
+            ratatui::backend::TestBackend::new(20, 10),
+            ratatui::TerminalOptions {
+                viewport: ratatui::Viewport::Inline(2),
+            },
+        )
+        .unwrap();
+        let mut harness =
+            AppHarness::from_app(App::from_terminal(terminal, 0)).app(|app| app.widgets(log_line));
+
+        harness.step_n(2).unwrap();
+
+        assert_eq!(harness.frame().size().height, 2);
+
+        let buffer = harness
+            .backend::<ratatui::backend::TestBackend>()
+            .unwrap()
+            .buffer();
+        let lines = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+
+        // Printed once per frame, pushing the viewport down below the printed lines.
+        assert_eq!(lines[0].trim_end(), "Downloaded 1 file");
+        assert_eq!(lines[1].trim_end(), "Downloaded 1 file");
+        assert!(lines[2..].iter().all(|line| line.trim().is_empty()));
+    }
+}
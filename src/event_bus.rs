@@ -0,0 +1,320 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use crate::{
+    redraw::Redraw, states::States, widget::id::WidgetId, Res, ResMut, State, WidgetParam,
+};
+
+/// The state holding every event of type `E` written in the last two frames.
+/// Registered with [`App::add_event`](crate::App::add_event).
+///
+/// Events are double buffered, so an event written during a frame can be read
+/// by widgets later in that frame, and by every widget during the next frame,
+/// after which it is dropped.
+pub struct EventQueue<E> {
+    previous: Vec<E>,
+    current: Vec<E>,
+    /// The id of the first event in `previous`, with ids counting up from there.
+    start: u64,
+    /// The id of the next event each reading widget hasn't read yet.
+    /// Kept behind a `RefCell`, so readers only need shared access to the queue.
+    readers: RefCell<HashMap<WidgetId, u64>>,
+}
+
+impl<E: 'static> State for EventQueue<E> {}
+
+impl<E> Default for EventQueue<E> {
+    fn default() -> Self {
+        Self {
+            previous: vec![],
+            current: vec![],
+            start: 0,
+            readers: RefCell::default(),
+        }
+    }
+}
+
+impl<E> EventQueue<E> {
+    /// Drops the events of the frame before last, making room for a new frame.
+    pub(crate) fn update(&mut self) {
+        self.start += self.previous.len() as u64;
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+    }
+
+    /// Forgets where every widget not in `widgets` is up to, once they have been removed.
+    pub(crate) fn retain_readers(&mut self, widgets: &HashSet<WidgetId>) {
+        self.readers
+            .get_mut()
+            .retain(|widget, _| widgets.contains(widget));
+    }
+
+    /// Returns the id of the first event the widget hasn't read yet.
+    fn cursor(&self, widget: WidgetId) -> u64 {
+        self.readers
+            .borrow()
+            .get(&widget)
+            .copied()
+            .unwrap_or(self.start)
+    }
+
+    /// Marks every event as read by the widget, returning the id of the first it hadn't read.
+    fn mark_read(&self, widget: WidgetId) -> u64 {
+        let end = self.end();
+
+        self.readers
+            .borrow_mut()
+            .insert(widget, end)
+            .unwrap_or(self.start)
+    }
+
+    /// The id that the next event written will have.
+    fn end(&self) -> u64 {
+        self.start + (self.previous.len() + self.current.len()) as u64
+    }
+
+    /// Returns every event with an id from `from` onwards that is still buffered.
+    fn read_from(&self, from: u64) -> impl Iterator<Item = &E> {
+        let skip = from.saturating_sub(self.start) as usize;
+
+        self.previous.iter().chain(&self.current).skip(skip)
+    }
+
+    /// Returns every event that is still buffered, without marking any as read.
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.read_from(self.start)
+    }
+}
+
+/// The id of the widget that is currently running,
+/// allowing [`EventReader`] to remember what each widget has read.
+pub(crate) struct CurrentWidget(pub(crate) WidgetId);
+
+impl State for CurrentWidget {}
+
+/// Writes events of type `E`, to be read by any [`EventReader<E>`].
+///
+/// Writing an event requests a redraw, so widgets that run earlier in the frame
+/// still get to read it while the app is in [`RunMode::Reactive`](crate::RunMode::Reactive).
+///
+/// Writing needs unique access to the [`EventQueue<E>`], so a widget can't take
+/// an `EventWriter<E>` alongside another `EventWriter<E>` or an [`EventReader<E>`] of the same type,
+/// or it panics when it runs, the same as taking two [`ResMut`]s of one state.
+pub struct EventWriter<'a, E: 'static> {
+    queue: ResMut<'a, EventQueue<E>>,
+    redraw: Res<'a, Redraw>,
+}
+
+impl<'a, E: 'static> WidgetParam for EventWriter<'a, E> {
+    type Item<'new> = EventWriter<'new, E>;
+
+    fn retrieve(resources: &States) -> Self::Item<'_> {
+        EventWriter {
+            queue: ResMut::<EventQueue<E>>::retrieve(resources),
+            redraw: Res::<Redraw>::retrieve(resources),
+        }
+    }
+}
+
+impl<E: 'static> EventWriter<'_, E> {
+    pub fn send(&mut self, event: E) {
+        self.queue.send(event);
+        self.redraw.request_redraw();
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = E>) {
+        for event in events {
+            self.send(event);
+        }
+    }
+}
+
+/// Reads the events of type `E` that this widget hasn't seen yet.
+///
+/// Each widget keeps its own place in the events,
+/// so every reader sees every event a single time.
+///
+/// Reading only needs shared access to the [`EventQueue<E>`], so a widget can take
+/// readers of the same type more than once, with every reader in a widget sharing its place.
+pub struct EventReader<'a, E: 'static> {
+    queue: Res<'a, EventQueue<E>>,
+    widget: WidgetId,
+}
+
+impl<'a, E: 'static> WidgetParam for EventReader<'a, E> {
+    type Item<'new> = EventReader<'new, E>;
+
+    fn retrieve(resources: &States) -> Self::Item<'_> {
+        EventReader {
+            queue: Res::<EventQueue<E>>::retrieve(resources),
+            widget: Res::<CurrentWidget>::retrieve(resources).0,
+        }
+    }
+}
+
+impl<E: 'static> EventReader<'_, E> {
+    /// Returns every event that hasn't been read by this widget yet, marking them as read.
+    pub fn read(&mut self) -> impl Iterator<Item = &E> {
+        let from = self.queue.mark_read(self.widget);
+
+        self.queue.read_from(from)
+    }
+
+    /// Returns whether this widget has already read every event.
+    pub fn is_empty(&self) -> bool {
+        self.queue.cursor(self.widget) >= self.queue.end()
+    }
+
+    /// Marks every event as read, without looking at them.
+    pub fn clear(&mut self) {
+        self.queue.mark_read(self.widget);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::KeyCode;
+
+    use crate::{
+        testing::{AppHarness, Log},
+        *,
+    };
+
+    use super::{EventQueue, EventReader, EventWriter};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Selected(usize);
+
+    fn details(mut selected: EventReader<Selected>, mut log: ResMut<Log>) -> WidgetResult {
+        for Selected(item) in selected.read() {
+            log.push(format!("details {item}"));
+        }
+        Ok(())
+    }
+
+    fn list(events: Res<Events>, mut selected: EventWriter<Selected>) -> WidgetResult {
+        if events.key(KeyCode::Down) {
+            selected.send(Selected(1));
+        }
+        Ok(())
+    }
+
+    fn status(mut selected: EventReader<Selected>, mut log: ResMut<Log>) -> WidgetResult {
+        for Selected(item) in selected.read() {
+            log.push(format!("status {item}"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn events_are_read_once_by_every_reader() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .add_event::<Selected>()
+                .widgets((details, list, status))
+        });
+
+        harness.push_key(KeyCode::Down);
+        harness.step_n(3).unwrap();
+
+        assert_eq!(harness.log().entries(), ["status 1", "details 1"]);
+    }
+
+    fn summary(
+        mut selected: EventReader<Selected>,
+        unread: EventReader<Selected>,
+        mut log: ResMut<Log>,
+    ) -> WidgetResult {
+        let count = selected.read().count();
+        log.push(format!("summary {count}, unread {}", !unread.is_empty()));
+        Ok(())
+    }
+
+    #[test]
+    fn a_widget_can_take_several_readers() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .add_event::<Selected>()
+                .widgets((list, summary))
+        });
+
+        harness.push_key(KeyCode::Down);
+        harness.step().unwrap();
+
+        assert_eq!(harness.log().entries(), ["summary 1, unread false"]);
+    }
+
+    #[test]
+    fn removed_widgets_stop_being_tracked() {
+        let id = WidgetId::new();
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default()).add_event::<Selected>().widgets((
+                details.with_id(id),
+                list,
+                status,
+            ))
+        });
+
+        harness.step().unwrap();
+        assert_eq!(
+            harness
+                .state::<EventQueue<Selected>>()
+                .readers
+                .borrow()
+                .len(),
+            2
+        );
+
+        harness = harness.app(|app| app.remove_widget(id));
+        assert_eq!(
+            harness
+                .state::<EventQueue<Selected>>()
+                .readers
+                .borrow()
+                .len(),
+            1
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum Panel {
+        Closed,
+        Open,
+    }
+
+    fn announce(mut selected: EventWriter<Selected>) -> WidgetResult {
+        selected.send(Selected(2));
+        Ok(())
+    }
+
+    #[test]
+    fn hook_widgets_are_tracked_when_a_widget_is_removed() {
+        let id = WidgetId::new();
+        let hook = WidgetId::new();
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .add_event::<Selected>()
+                .app_state(Panel::Closed)
+                .on_exit(Panel::Closed, announce)
+                .on_enter(Panel::Open, details.with_id(hook))
+                .on_exit(Panel::Open, details.with_id(hook))
+                .widgets(status.with_id(id))
+        });
+
+        harness.state_mut::<NextState<Panel>>().set(Panel::Open);
+        harness.step().unwrap();
+
+        harness = harness.app(|app| app.remove_widget(id));
+
+        // The hook runs again while the event it read is still buffered.
+        harness.state_mut::<NextState<Panel>>().set(Panel::Closed);
+        harness.step().unwrap();
+
+        assert_eq!(harness.log().entries(), ["details 2", "status 2"]);
+    }
+}
@@ -1,52 +1,345 @@
+use std::{
+    any::Any,
+    sync::mpsc::{channel, Receiver, SendError, Sender},
+};
+
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 
-use crate::State;
+use crate::{redraw::RedrawHandle, State};
 
-/// A state that wraps over the events from crossterm
-#[derive(Default, Clone, State)]
+/// A state that wraps over the events from crossterm.
+///
+/// Holds every event that arrived since the last frame, in the order they arrived,
+/// so fast typing or pasted text is handled within a single frame.
+///
+/// # Migrating
+///
+/// `Events` used to hold a single event in a public `event` field.
+/// Code that matched on that field should loop over the events instead:
+///
+/// ```
+/// # use widgetui::{crossterm::event::Event, *};
+/// fn widget(events: Res<Events>) -> WidgetResult {
+///     // Was `if let Some(Event::Resize(..)) = &events.event { ... }`
+///     for event in events.iter() {
+///         if let Event::Resize(..) = event {
+///             // ...
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// Key checks such as [`Events::key`] and [`Events::consume_key`] work as before,
+/// looking through every event of the frame.
+#[derive(Default, State)]
 pub struct Events {
-    pub event: Option<Event>,
+    events: Vec<Event>,
     pub(crate) exit: bool,
+    exit_value: Option<Box<dyn Any>>,
+}
+
+/// Clones the events and whether the app is exiting, but not the value it exits with,
+/// which is only ever handed back once.
+impl Clone for Events {
+    fn clone(&self) -> Self {
+        Self {
+            events: self.events.clone(),
+            exit: self.exit,
+            exit_value: None,
+        }
+    }
 }
 
 impl Events {
+    /// Replaces the events with those of a new frame.
+    pub(crate) fn set_events(&mut self, events: Vec<Event>) {
+        self.events = events;
+    }
+
+    /// Returns every event that hasn't been consumed yet this frame.
+    pub fn iter(&self) -> std::slice::Iter<'_, Event> {
+        self.events.iter()
+    }
+
+    /// Returns every key press that hasn't been consumed yet this frame.
+    pub fn key_presses(&self) -> impl Iterator<Item = &KeyEvent> {
+        self.events.iter().filter_map(|event| match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => Some(key_event),
+            _ => None,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Removes the first event matching the predicate, returning it.
+    /// The event will not be passed on to future widgets.
+    pub fn consume(&mut self, predicate: impl FnMut(&Event) -> bool) -> Option<Event> {
+        let index = self.events.iter().position(predicate)?;
+        Some(self.events.remove(index))
+    }
+
+    /// Keeps only the events matching the predicate, consuming the rest.
+    pub fn retain(&mut self, predicate: impl FnMut(&Event) -> bool) {
+        self.events.retain(predicate);
+    }
+
     /// Returns whether a key was pressed this frame.
     pub fn key(&self, code: KeyCode) -> bool {
-        if let Some(Event::Key(key_event)) = self.event {
-            if key_event.code == code && key_event.kind == KeyEventKind::Press {
-                return true;
-            }
-        }
-
-        false
+        self.key_presses().any(|key_event| key_event.code == code)
     }
 
     /// Returns whether a Key Event was completed this frame.
     pub fn key_event(&self, check_event: KeyEvent) -> bool {
-        if let Some(Event::Key(key_event)) = self.event {
-            if key_event == check_event && key_event.kind == KeyEventKind::Press {
-                return true;
-            }
-        }
-
-        false
+        self.key_presses()
+            .any(|key_event| *key_event == check_event)
     }
 
     /// Returns whether a key was pressed this frame.
-    /// This will consume the key, not passing it on to future widgets.
+    /// This will consume the first press of the key, not passing it on to future widgets.
     pub fn consume_key(&mut self, code: KeyCode) -> bool {
-        if let Some(Event::Key(key_event)) = self.event {
-            if key_event.code == code && key_event.kind == KeyEventKind::Press {
-                self.event = None;
-                return true;
-            }
-        }
-
-        false
+        self.consume(|event| {
+            matches!(event, Event::Key(key_event)
+                if key_event.code == code && key_event.kind == KeyEventKind::Press)
+        })
+        .is_some()
     }
 
     /// Let the app know you want to quit.
     pub fn register_exit(&mut self) {
         self.exit = true;
     }
+
+    /// Let the app know you want to quit, returning the given value from
+    /// [`App::run_returning`](crate::App::run_returning).
+    pub fn exit_with<T: 'static>(&mut self, value: T) {
+        self.exit = true;
+        self.exit_value = Some(Box::new(value));
+    }
+
+    /// Takes the value the app exited with, if it is a `T`.
+    pub(crate) fn take_exit_value<T: 'static>(&mut self) -> Option<T> {
+        if !self.exit_value.as_ref()?.is::<T>() {
+            return None;
+        }
+
+        let value = self.exit_value.take()?.downcast::<T>().ok()?;
+        Some(*value)
+    }
+}
+
+impl<'a> IntoIterator for &'a Events {
+    type Item = &'a Event;
+    type IntoIter = std::slice::Iter<'a, Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Sends custom events into the app from any thread, waking it up to handle them.
+/// Created with [`App::event_sender`](crate::App::event_sender) or [`UserEvents::sender`].
+pub struct EventSender<T> {
+    sender: Sender<T>,
+    redraw: RedrawHandle,
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            redraw: self.redraw.clone(),
+        }
+    }
+}
+
+impl<T> EventSender<T> {
+    pub(crate) fn new(sender: Sender<T>, redraw: RedrawHandle) -> Self {
+        Self { sender, redraw }
+    }
+
+    /// Sends the event, which will be available in [`UserEvents<T>`] on the next frame.
+    /// Returns the event back if the app has already closed.
+    pub fn send(&self, event: T) -> Result<(), SendError<T>> {
+        self.sender.send(event)?;
+        self.redraw.request_redraw();
+        Ok(())
+    }
+}
+
+/// A state holding the custom events of type `T` that were sent since the last frame,
+/// through an [`EventSender<T>`].
+pub struct UserEvents<T> {
+    events: Vec<T>,
+    sender: EventSender<T>,
+    receiver: Receiver<T>,
+}
+
+impl<T: 'static> State for UserEvents<T> {}
+
+impl<T> UserEvents<T> {
+    pub(crate) fn new(redraw: RedrawHandle) -> Self {
+        let (sender, receiver) = channel();
+
+        Self {
+            events: vec![],
+            sender: EventSender::new(sender, redraw),
+            receiver,
+        }
+    }
+
+    /// Replaces the events with everything sent since the last frame.
+    pub(crate) fn receive(&mut self) {
+        self.events.clear();
+        self.events.extend(self.receiver.try_iter());
+    }
+
+    /// Returns a new sender, which can be moved to another thread.
+    pub fn sender(&self) -> EventSender<T> {
+        self.sender.clone()
+    }
+
+    /// Returns every event that hasn't been taken yet this frame.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.events.iter()
+    }
+
+    /// Takes every event, so they aren't passed on to future widgets.
+    pub fn take(&mut self) -> Vec<T> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a UserEvents<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::{testing::AppHarness, *};
+
+    use super::UserEvents;
+
+    #[derive(Default, State)]
+    struct Typed(String);
+
+    fn submit(mut events: ResMut<Events>, mut typed: ResMut<Typed>) -> WidgetResult {
+        if events.consume_key(KeyCode::Enter) {
+            typed.0.push('\n');
+        }
+        Ok(())
+    }
+
+    fn typing(events: Res<Events>, mut typed: ResMut<Typed>) -> WidgetResult {
+        for key_event in events.key_presses() {
+            if let KeyCode::Char(c) = key_event.code {
+                typed.0.push(c);
+            }
+        }
+        Ok(())
+    }
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn every_event_is_handled_in_one_frame() {
+        let mut harness =
+            AppHarness::new(1, 1).app(|app| app.states(Typed::default()).widgets((submit, typing)));
+
+        harness.push_events([
+            key('h'),
+            Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+            key('i'),
+        ]);
+        harness.step().unwrap();
+
+        assert_eq!(harness.state::<Typed>().0, "\nhi");
+        assert_eq!(harness.state::<Events>().len(), 2);
+    }
+
+    #[derive(Default, State)]
+    struct Lines(Vec<String>);
+
+    fn tail(events: Res<UserEvents<String>>, mut lines: ResMut<Lines>) -> WidgetResult {
+        lines.0.extend(events.iter().cloned());
+        Ok(())
+    }
+
+    #[test]
+    fn events_are_sent_from_other_threads() {
+        let mut harness =
+            AppHarness::new(1, 1).app(|app| app.states(Lines::default()).widgets(tail));
+        let sender = harness.app_mut().event_sender::<String>();
+
+        std::thread::spawn(move || {
+            sender.send("first".to_string()).unwrap();
+            sender.send("second".to_string()).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        harness.step_n(2).unwrap();
+
+        assert_eq!(harness.state::<Lines>().0, ["first", "second"]);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Choice(&'static str);
+
+    fn picker(mut events: ResMut<Events>) -> WidgetResult {
+        if events.key(KeyCode::Enter) {
+            events.exit_with(Choice("second"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn exits_with_a_value() {
+        let mut harness = AppHarness::new(1, 1).app(|app| app.widgets(picker));
+
+        harness.step().unwrap();
+        assert!(!harness.exited());
+
+        harness.push_key(KeyCode::Enter);
+        harness.step().unwrap();
+
+        assert!(harness.exited());
+        assert_eq!(harness.take_exit_value::<u32>(), None);
+        assert_eq!(harness.take_exit_value(), Some(Choice("second")));
+    }
+
+    #[test]
+    fn clones_leave_the_exit_value_behind() {
+        let mut events = Events::default();
+        events.exit_with(Choice("first"));
+
+        let mut clone = events.clone();
+
+        assert!(clone.exit);
+        assert_eq!(clone.take_exit_value::<Choice>(), None);
+        assert_eq!(events.take_exit_value(), Some(Choice("first")));
+    }
 }
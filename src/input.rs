@@ -0,0 +1,361 @@
+use std::{
+    any::TypeId,
+    io::{self, Stderr, Stdout},
+    sync::mpsc::{Receiver, RecvTimeoutError, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+use crossterm::event::Event;
+use ratatui::prelude::CrosstermBackend;
+
+use crate::redraw::Redraw;
+
+/// Where the app reads the events that fill [`Events`](crate::Events) from.
+pub(crate) enum EventSource {
+    /// The real terminal, read through crossterm.
+    Terminal,
+    /// Events sent through an [`EventSender<Event>`](crate::EventSender),
+    /// created with [`App::input_sender`](crate::App::input_sender).
+    Channel(Receiver<Event>),
+    /// Nothing, so the app only wakes up on its own.
+    Empty,
+}
+
+impl EventSource {
+    /// Picks the source for a terminal with the given backend, only reading from crossterm
+    /// when the backend is exactly `CrosstermBackend<Stdout>` or `CrosstermBackend<Stderr>`.
+    /// Other backends opt in with [`App::terminal_input`](crate::App::terminal_input).
+    pub(crate) fn for_backend<B: 'static>() -> Self {
+        let backend = TypeId::of::<B>();
+
+        if backend == TypeId::of::<CrosstermBackend<Stdout>>()
+            || backend == TypeId::of::<CrosstermBackend<Stderr>>()
+        {
+            Self::Terminal
+        } else {
+            Self::Empty
+        }
+    }
+
+    /// Returns the next event if one is ready, without waiting.
+    fn try_next(&mut self) -> io::Result<Option<Event>> {
+        match self {
+            Self::Terminal => {
+                if crossterm::event::poll(Duration::ZERO)? {
+                    Ok(Some(crossterm::event::read()?))
+                } else {
+                    Ok(None)
+                }
+            }
+            Self::Channel(receiver) => match receiver.try_recv() {
+                Ok(event) => Ok(Some(event)),
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => Ok(None),
+            },
+            Self::Empty => Ok(None),
+        }
+    }
+
+    /// Waits up to `timeout` for the next event.
+    fn poll(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+        match self {
+            Self::Terminal => {
+                if crossterm::event::poll(timeout)? {
+                    Ok(Some(crossterm::event::read()?))
+                } else {
+                    Ok(None)
+                }
+            }
+            Self::Channel(receiver) => match receiver.recv_timeout(timeout) {
+                Ok(event) => Ok(Some(event)),
+                Err(RecvTimeoutError::Timeout) => Ok(None),
+                // Every sender was dropped, so nothing more will arrive.
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(timeout);
+                    Ok(None)
+                }
+            },
+            Self::Empty => {
+                thread::sleep(timeout);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Reads events from the [`EventSource`] of the app,
+/// sleeping until an event arrives, the app is woken up or a timeout passes.
+pub(crate) struct Input {
+    source: EventSource,
+    #[cfg(unix)]
+    waiter: Option<unix::Waiter>,
+}
+
+impl Input {
+    pub(crate) fn new(source: EventSource) -> Self {
+        Self {
+            source,
+            #[cfg(unix)]
+            waiter: None,
+        }
+    }
+
+    pub(crate) fn set_source(&mut self, source: EventSource) {
+        self.source = source;
+    }
+
+    /// Prepares to wait for events, letting redraws requested from any thread wake the app.
+    #[cfg(unix)]
+    pub(crate) fn start(&mut self, redraw: &Redraw) -> io::Result<()> {
+        let terminal = matches!(self.source, EventSource::Terminal);
+        let waiter = self.waiter()?;
+
+        if terminal {
+            waiter.watch_terminal()?;
+        }
+
+        let waker = waiter.waker();
+        redraw.set_waker(Box::new(move || {
+            let _ = waker.wake();
+        }));
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn start(&mut self, _redraw: &Redraw) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Returns a socket that wakes the app up whenever a byte is written to it,
+    /// which a signal handler can safely write to.
+    #[cfg(unix)]
+    pub(crate) fn signal_pipe(&mut self) -> io::Result<std::os::unix::net::UnixStream> {
+        self.waiter()?.signal_pipe()
+    }
+
+    #[cfg(unix)]
+    fn waiter(&mut self) -> io::Result<&mut unix::Waiter> {
+        if self.waiter.is_none() {
+            self.waiter = Some(unix::Waiter::new()?);
+        }
+
+        Ok(self.waiter.as_mut().expect("The waiter was just created"))
+    }
+
+    /// Returns the next event if one is ready, without waiting.
+    pub(crate) fn try_next(&mut self) -> io::Result<Option<Event>> {
+        self.source.try_next()
+    }
+
+    /// Returns the next event, or `None` once `timeout` passes or the app is woken up.
+    /// Waits until one of those happens if there is no timeout.
+    pub(crate) fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Option<Event>> {
+        // Without a way to be woken up, the app has to check back in every so often.
+        const FALLBACK_INTERVAL: Duration = Duration::from_millis(16);
+
+        if let Some(event) = self.source.try_next()? {
+            return Ok(Some(event));
+        }
+
+        #[cfg(unix)]
+        if let Some(waiter) = &mut self.waiter {
+            waiter.wait(timeout)?;
+            return self.source.try_next();
+        }
+
+        let timeout = timeout.map_or(FALLBACK_INTERVAL, |timeout| timeout.min(FALLBACK_INTERVAL));
+        self.source.poll(timeout)
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        fs::File,
+        io::{self, IsTerminal, Read},
+        os::{fd::AsRawFd, unix::net::UnixStream},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use mio::{unix::SourceFd, Events, Interest, Poll, Token, Waker};
+
+    const WAKE: Token = Token(0);
+    const SIGNAL: Token = Token(1);
+    const TERMINAL: Token = Token(2);
+
+    /// Sleeps until the terminal has input, a signal handler writes to the signal socket
+    /// or another thread uses the waker.
+    pub(super) struct Waiter {
+        poll: Poll,
+        events: Events,
+        waker: Arc<Waker>,
+        signals: mio::net::UnixStream,
+        signal_writer: UnixStream,
+        /// The terminal, when it had to be opened because stdin isn't one.
+        tty: Option<File>,
+        watching_terminal: bool,
+    }
+
+    impl Waiter {
+        pub(super) fn new() -> io::Result<Self> {
+            let poll = Poll::new()?;
+            let waker = Arc::new(Waker::new(poll.registry(), WAKE)?);
+
+            let (reader, signal_writer) = UnixStream::pair()?;
+            reader.set_nonblocking(true)?;
+            signal_writer.set_nonblocking(true)?;
+
+            let mut signals = mio::net::UnixStream::from_std(reader);
+            poll.registry()
+                .register(&mut signals, SIGNAL, Interest::READABLE)?;
+
+            Ok(Self {
+                poll,
+                events: Events::with_capacity(8),
+                waker,
+                signals,
+                signal_writer,
+                tty: None,
+                watching_terminal: false,
+            })
+        }
+
+        pub(super) fn waker(&self) -> Arc<Waker> {
+            self.waker.clone()
+        }
+
+        pub(super) fn signal_pipe(&self) -> io::Result<UnixStream> {
+            self.signal_writer.try_clone()
+        }
+
+        /// Wakes up whenever the terminal crossterm reads from has input.
+        pub(super) fn watch_terminal(&mut self) -> io::Result<()> {
+            if self.watching_terminal {
+                return Ok(());
+            }
+
+            let fd = if io::stdin().is_terminal() {
+                io::stdin().as_raw_fd()
+            } else {
+                self.tty.insert(File::open("/dev/tty")?).as_raw_fd()
+            };
+
+            self.poll
+                .registry()
+                .register(&mut SourceFd(&fd), TERMINAL, Interest::READABLE)?;
+            self.watching_terminal = true;
+
+            Ok(())
+        }
+
+        pub(super) fn wait(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+            match self.poll.poll(&mut self.events, timeout) {
+                // Interrupted by a signal, which should wake the app up anyway.
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                result => result?,
+            }
+
+            // Empty the socket, so signals can wake the app up again.
+            let mut buffer = [0; 32];
+            while matches!(self.signals.read(&mut buffer), Ok(1..)) {}
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use crate::{app::MAX_EVENTS_PER_FRAME, testing::Log, *};
+
+    fn quit(mut events: ResMut<Events>) -> WidgetResult {
+        if events.key(KeyCode::Char('q')) {
+            let count = events.len();
+            events.exit_with(count);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn runs_headless_from_sent_events() {
+        let terminal = Terminal::new(TestBackend::new(10, 2)).unwrap();
+        let mut app = App::from_terminal(terminal, 0).widgets(quit);
+
+        let sender = app.input_sender();
+        for code in [KeyCode::Char('a'), KeyCode::Char('q')] {
+            sender
+                .send(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+                .unwrap();
+        }
+
+        let exit = app.run_returning::<usize>().unwrap();
+
+        assert_eq!(exit.value, Some(2));
+    }
+
+    fn shut_down(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("shutdown");
+        Ok(())
+    }
+
+    #[test]
+    fn ctrl_c_stops_the_app() {
+        let terminal = Terminal::new(TestBackend::new(10, 2)).unwrap();
+        let mut app = App::from_terminal(terminal, 0)
+            .ctrl_c_exits(true)
+            .states(Log::default())
+            .add_widgets(Stage::Shutdown, shut_down);
+
+        let sender = app.input_sender();
+        sender
+            .send(Event::Key(KeyEvent::new(
+                KeyCode::Char('c'),
+                KeyModifiers::CONTROL,
+            )))
+            .unwrap();
+
+        let exit = app.run_returning::<()>().unwrap();
+
+        assert_eq!(Res::<Log>::retrieve(&exit.states).entries(), ["shutdown"]);
+    }
+
+    fn count_frames(mut events: ResMut<Events>, mut log: ResMut<Log>) -> WidgetResult {
+        log.push(events.len().to_string());
+        if events.key(KeyCode::Char('q')) {
+            events.register_exit();
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn floods_of_events_are_spread_over_frames() {
+        let terminal = Terminal::new(TestBackend::new(10, 2)).unwrap();
+        let mut app = App::from_terminal(terminal, 0)
+            .states(Log::default())
+            .widgets(count_frames);
+
+        let sender = app.input_sender();
+        for _ in 0..MAX_EVENTS_PER_FRAME + 10 {
+            sender.send(Event::FocusGained).unwrap();
+        }
+        sender
+            .send(Event::Key(KeyEvent::new(
+                KeyCode::Char('q'),
+                KeyModifiers::NONE,
+            )))
+            .unwrap();
+
+        let exit = app.run_returning::<()>().unwrap();
+        let log = Res::<Log>::retrieve(&exit.states);
+
+        assert_eq!(
+            log.entries(),
+            [MAX_EVENTS_PER_FRAME.to_string(), "11".to_string()]
+        );
+    }
+}
@@ -12,9 +12,9 @@ Constraints:
 - <Num: Max
 - Num ; Num: Ratio
 
-    Then Either , to make new row, or => {} to add horizontal limits to row.
-    If horizontal limits, then
-    constraint and optionally more
+  Then Either , to make new row, or => {} to add horizontal limits to row.
+  If horizontal limits, then
+  constraint and optionally more
 
 Ex:
 Custom Chunks Example
@@ -7,24 +7,50 @@ extern crate anyhow;
 extern crate tui_helper_proc_macro;
 
 pub mod app;
+pub mod app_state;
 pub mod chunks;
+pub mod commands;
+pub mod event_bus;
 pub mod events;
+pub(crate) mod input;
 pub mod layout;
+pub mod redraw;
 pub mod set;
 pub mod setup;
+pub mod stage;
 pub mod states;
+pub mod tasks;
+pub mod timer;
+pub mod tween;
 pub mod widget;
 
+/// Headless Testing Tools
+pub mod testing;
+
 /// Pre-Built Widgets
 pub mod widgets;
 
-pub use app::App;
+pub use app::{App, AppExit};
+pub use app_state::{in_state, AppState, NextState};
 pub use chunks::Chunks;
-pub use events::Events;
-pub use set::Set;
-pub use setup::{WidgetBackend, WidgetFrame, WidgetTerminal};
+pub use commands::Commands;
+pub use event_bus::{EventQueue, EventReader, EventWriter};
+pub use events::{EventSender, Events, UserEvents};
+pub use redraw::{Redraw, RedrawHandle, RunMode};
+pub use set::{Dependencies, Set};
+pub use setup::{AppConfig, WidgetBackend, WidgetFrame, WidgetTerminal};
+pub use stage::Stage;
 pub use states::{MultiFromStates, State};
-pub use widget::{into_widget_set::IntoWidgetSet, WidgetResult};
+pub use tasks::Tasks;
+pub use timer::{Stopwatch, Timer, TimerMode, Timers};
+pub use tween::{Animatable, Easing, Tween, Tweens};
+pub use widget::{
+    condition::{every, on_event, on_key, once_after, Condition},
+    id::WidgetId,
+    into_widget::IntoWidget,
+    into_widget_set::IntoWidgetSet,
+    WidgetResult,
+};
 
 pub use tui_helper_proc_macro::set;
 pub use tui_helper_proc_macro::State;
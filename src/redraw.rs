@@ -0,0 +1,224 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::State;
+
+/// Decides when the app runs a frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// Runs a frame every clock tick, whether or not anything happened.
+    #[default]
+    Continuous,
+    /// Sleeps until an event arrives or a redraw is requested through [`Redraw`],
+    /// only running a frame when there is something to draw.
+    ///
+    /// The app also wakes up on its own when a [`Timer`](crate::Timer) in
+    /// [`Timers`](crate::Timers) finishes, when a [`Stage::FixedUpdate`](crate::Stage::FixedUpdate)
+    /// step is due, when a delay given to [`Redraw::request_redraw_in`] passes,
+    /// and when a [`NextState`](crate::NextState) transition is requested.
+    Reactive,
+}
+
+/// Wakes the app up while it is waiting for events.
+pub(crate) type Waker = Box<dyn Fn() + Send + Sync>;
+
+struct Shared {
+    pending: AtomicBool,
+    waker: OnceLock<Waker>,
+    deadline: Mutex<Option<Instant>>,
+}
+
+impl Shared {
+    fn request_redraw(&self) {
+        self.pending.store(true, Ordering::Release);
+
+        if let Some(wake) = self.waker.get() {
+            wake();
+        }
+    }
+}
+
+/// The state used to request a new frame while the app is in [`RunMode::Reactive`].
+///
+/// A redraw is pending when the app starts, so the first frame is always drawn.
+#[derive(Clone, State)]
+pub struct Redraw {
+    shared: Arc<Shared>,
+}
+
+impl Default for Redraw {
+    fn default() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                pending: AtomicBool::new(true),
+                waker: OnceLock::new(),
+                deadline: Mutex::new(None),
+            }),
+        }
+    }
+}
+
+impl Redraw {
+    /// Requests that another frame runs, even if no event arrives.
+    pub fn request_redraw(&self) {
+        self.shared.request_redraw();
+    }
+
+    /// Requests that a frame runs once the given delay has passed, even if no event arrives,
+    /// such as to hide a message once it expires.
+    pub fn request_redraw_in(&self, delay: Duration) {
+        let deadline = Instant::now() + delay;
+        let mut next = self.shared.deadline.lock().unwrap();
+
+        if next.is_none_or(|next| deadline < next) {
+            *next = Some(deadline);
+        }
+    }
+
+    /// Returns a handle that can request redraws from other threads.
+    pub fn handle(&self) -> RedrawHandle {
+        RedrawHandle {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Sets what wakes the app up when a redraw is requested from another thread.
+    pub(crate) fn set_waker(&self, waker: Waker) {
+        let _ = self.shared.waker.set(waker);
+    }
+
+    /// Clears the pending redraw, returning whether there was one.
+    pub(crate) fn take(&self) -> bool {
+        self.shared.pending.swap(false, Ordering::AcqRel)
+    }
+
+    /// Returns when the soonest delayed redraw is due.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        *self.shared.deadline.lock().unwrap()
+    }
+
+    /// Forgets the delayed redraw if it is due by `now`.
+    pub(crate) fn clear_passed_deadline(&self, now: Instant) {
+        let mut deadline = self.shared.deadline.lock().unwrap();
+
+        if deadline.is_some_and(|deadline| deadline <= now) {
+            *deadline = None;
+        }
+    }
+}
+
+/// A handle to the [`Redraw`] state that can be sent to other threads,
+/// waking the app up once they have something new to show.
+#[derive(Clone)]
+pub struct RedrawHandle {
+    shared: Arc<Shared>,
+}
+
+impl RedrawHandle {
+    /// Requests that another frame runs, even if no event arrives.
+    pub fn request_redraw(&self) {
+        self.shared.request_redraw();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use crate::*;
+
+    #[derive(Default, State)]
+    struct Frames(usize);
+
+    fn reactive_app() -> App {
+        let terminal = Terminal::new(TestBackend::new(10, 2)).unwrap();
+        App::from_terminal(terminal, 0)
+            .run_mode(RunMode::Reactive)
+            .states(Frames::default())
+    }
+
+    fn start_timer(mut timers: ResMut<Timers>) -> WidgetResult {
+        timers.start("done", Timer::once(Duration::from_millis(30)));
+        Ok(())
+    }
+
+    fn exit_when_done(
+        timers: Res<Timers>,
+        mut frames: ResMut<Frames>,
+        mut events: ResMut<Events>,
+    ) -> WidgetResult {
+        frames.0 += 1;
+        if timers.just_finished("done") {
+            events.exit_with(frames.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reactive_apps_wake_up_for_timers() {
+        let app = reactive_app()
+            .add_widgets(Stage::Startup, start_timer)
+            .widgets(exit_when_done);
+
+        let started = Instant::now();
+        let exit = app.run_returning::<usize>().unwrap();
+
+        // The first frame, then the one the timer finishing woke the app up for,
+        // without waking up in between.
+        assert_eq!(exit.value, Some(2));
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+
+    fn exit_on_message(
+        messages: Res<UserEvents<&'static str>>,
+        mut frames: ResMut<Frames>,
+        mut events: ResMut<Events>,
+    ) -> WidgetResult {
+        frames.0 += 1;
+        if messages.iter().next().is_some() {
+            events.exit_with(frames.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reactive_apps_wake_up_for_events_from_other_threads() {
+        let mut app = reactive_app().widgets(exit_on_message);
+
+        let sender = app.event_sender::<&'static str>();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            sender.send("done").unwrap();
+        });
+
+        let exit = app.run_returning::<usize>().unwrap();
+
+        assert_eq!(exit.value, Some(2));
+    }
+
+    #[test]
+    fn redraws_are_requested_across_threads() {
+        let redraw = Redraw::default();
+
+        assert!(redraw.take());
+        assert!(!redraw.take());
+
+        let handle = redraw.handle();
+        thread::spawn(move || handle.request_redraw())
+            .join()
+            .unwrap();
+
+        assert!(redraw.take());
+        assert!(!redraw.take());
+    }
+}
@@ -1,37 +1,190 @@
-use crate::App;
+use std::any::type_name;
+
+use crate::{
+    widget::order::{Label, WidgetOrder},
+    App,
+};
 
 /// This is a trait that will allow you to abstract the way you add widgets and states.
+///
+/// Every widget a set adds is labeled with the type of the set,
+/// so other widgets can be ordered before or after the whole set.
+///
+/// Sets are unique by default, so adding a set with the same [`Set::name`] a second time
+/// does nothing, which lets any number of sets safely depend on the same set.
 pub trait Set {
     fn register_set(&self, app: App) -> App;
+
+    /// The name of the set, used to check whether it was already added.
+    fn name(&self) -> &str {
+        type_name::<Self>()
+    }
+
+    /// Whether adding the set a second time should be skipped.
+    /// Sets that can be configured differently each time they are added should return `false`.
+    fn is_unique(&self) -> bool {
+        true
+    }
+
+    /// The sets this set needs, which are added before it if they haven't been added yet.
+    ///
+    /// To configure a dependency, add it to the app before this set.
+    fn dependencies(&self) -> Dependencies {
+        Dependencies::default()
+    }
+
+    /// Runs once every set has been added, just before the startup widgets run,
+    /// or straight away for sets added after that.
+    ///
+    /// States can be read and changed through [`App::state`] and [`App::state_mut`].
+    fn finish(&self, app: App) -> App {
+        app
+    }
+
+    /// Runs once the app stops running, before the terminal is restored.
+    fn cleanup(&self, app: App) -> App {
+        app
+    }
+
+    /// Gives every widget the set adds the label `L`.
+    fn label<L: 'static>(self) -> OrderedSet<Self>
+    where
+        Self: Sized,
+    {
+        OrderedSet::new(self).label::<L>()
+    }
+
+    /// Runs every widget the set adds before every widget labeled `L` in the same stage.
+    fn before<L: 'static>(self) -> OrderedSet<Self>
+    where
+        Self: Sized,
+    {
+        OrderedSet::new(self).before::<L>()
+    }
+
+    /// Runs every widget the set adds after every widget labeled `L` in the same stage.
+    fn after<L: 'static>(self) -> OrderedSet<Self>
+    where
+        Self: Sized,
+    {
+        OrderedSet::new(self).after::<L>()
+    }
+}
+
+/// A set with extra ordering constraints for every widget it adds.
+/// Created with [`Set::label`], [`Set::before`] or [`Set::after`].
+pub struct OrderedSet<S> {
+    set: S,
+    order: WidgetOrder,
+}
+
+impl<S> OrderedSet<S> {
+    pub fn new(set: S) -> Self {
+        Self {
+            set,
+            order: WidgetOrder::default(),
+        }
+    }
+
+    /// Gives every widget the set adds the label `L`.
+    pub fn label<L: 'static>(mut self) -> Self {
+        self.order.labels.push(Label::of::<L>());
+        self
+    }
+
+    /// Runs every widget the set adds before every widget labeled `L` in the same stage.
+    pub fn before<L: 'static>(mut self) -> Self {
+        self.order.before.push(Label::of::<L>());
+        self
+    }
+
+    /// Runs every widget the set adds after every widget labeled `L` in the same stage.
+    pub fn after<L: 'static>(mut self) -> Self {
+        self.order.after.push(Label::of::<L>());
+        self
+    }
+}
+
+impl<S: Set + 'static> Set for OrderedSet<S> {
+    fn register_set(&self, app: App) -> App {
+        app.with_set_order(self.order.clone(), |app| {
+            app.register_set_widgets(&self.set)
+        })
+    }
+
+    fn name(&self) -> &str {
+        self.set.name()
+    }
+
+    fn is_unique(&self) -> bool {
+        self.set.is_unique()
+    }
+
+    fn dependencies(&self) -> Dependencies {
+        self.set.dependencies()
+    }
+
+    fn finish(&self, app: App) -> App {
+        self.set.finish(app)
+    }
+
+    fn cleanup(&self, app: App) -> App {
+        self.set.cleanup(app)
+    }
+}
+
+/// The sets that a set depends on, returned from [`Set::dependencies`].
+#[derive(Default)]
+pub struct Dependencies {
+    sets: Vec<Box<dyn FnOnce(App) -> App>>,
+}
+
+impl Dependencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given set as a dependency.
+    pub fn with<S: Set + 'static>(mut self, set: S) -> Self {
+        self.sets.push(Box::new(move |app| app.register_set(set)));
+        self
+    }
+
+    pub(crate) fn register(self, mut app: App) -> App {
+        for register in self.sets {
+            app = register(app);
+        }
+        app
+    }
 }
 
 pub trait Sets {
-    fn register_sets(&self, app: App) -> App;
+    fn register_sets(self, app: App) -> App;
 }
 
 impl<A> Sets for A
 where
-    A: Set,
+    A: Set + 'static,
 {
-    fn register_sets(&self, app: App) -> App {
-        self.register_set(app)
+    fn register_sets(self, app: App) -> App {
+        app.register_set(self)
     }
 }
 
 impl<A> Sets for (A,)
 where
-    A: Set,
+    A: Set + 'static,
 {
-    fn register_sets(&self, app: App) -> App {
-        self.0.register_set(app)
+    fn register_sets(self, app: App) -> App {
+        app.register_set(self.0)
     }
 }
 
 macro_rules! impl_sets {
     ($($t:ident $val:tt)*) => {
-        impl<$($t,)*> Sets for ($($t,)*) where $($t: Set,)* {
-            fn register_sets(&self, mut app: App) -> App {
-                $(app = self.$val.register_set(app);)*
+        impl<$($t,)*> Sets for ($($t,)*) where $($t: Set + 'static,)* {
+            fn register_sets(self, mut app: App) -> App {
+                $(app = app.register_set(self.$val);)*
                 app
             }
         }
@@ -48,3 +201,101 @@ impl_sets! {A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 }
 impl_sets! {A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 }
 impl_sets! {A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9 }
 impl_sets! {A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9 K 10}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        testing::{AppHarness, Log},
+        *,
+    };
+
+    use super::Dependencies;
+
+    fn layout(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("layout");
+        Ok(())
+    }
+
+    fn draw(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("draw");
+        Ok(())
+    }
+
+    fn input(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("input");
+        Ok(())
+    }
+
+    #[set]
+    fn Draw(app: App) -> App {
+        app.widgets(draw)
+    }
+
+    #[set]
+    fn Layout(app: App) -> App {
+        app.widgets(layout)
+    }
+
+    #[test]
+    fn sets_are_ordered_by_constraints() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .widgets(input.after::<Layout>().before::<Draw>())
+                .sets((Draw, Layout.before::<Draw>()))
+        });
+
+        harness.step().unwrap();
+
+        assert_eq!(harness.log().entries(), ["layout", "input", "draw"]);
+    }
+
+    #[test]
+    fn cycles_stop_the_app() {
+        let mut harness = AppHarness::new(1, 1)
+            .app(|app| app.sets((Draw.before::<Layout>(), Layout.before::<Draw>())));
+
+        assert!(harness.step().is_err());
+    }
+
+    #[derive(Default, State)]
+    struct Hooks(Vec<&'static str>);
+
+    struct Dashboard;
+
+    impl Set for Dashboard {
+        fn register_set(&self, app: App) -> App {
+            app.widgets(draw)
+        }
+
+        fn dependencies(&self) -> Dependencies {
+            Dependencies::new().with(Layout)
+        }
+
+        fn finish(&self, app: App) -> App {
+            app.states(Hooks(vec!["finish"]))
+        }
+
+        fn cleanup(&self, app: App) -> App {
+            app.state_mut::<Hooks>().0.push("cleanup");
+            app
+        }
+    }
+
+    #[test]
+    fn sets_are_unique_and_add_dependencies() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default()).sets((
+                Layout.before::<Draw>(),
+                Dashboard,
+                Dashboard.label::<Draw>(),
+            ))
+        });
+
+        harness.step().unwrap();
+        assert_eq!(harness.log().entries(), ["layout", "draw"]);
+        assert_eq!(harness.state::<Hooks>().0, ["finish"]);
+
+        harness.cleanup();
+        assert_eq!(harness.state::<Hooks>().0, ["finish", "cleanup"]);
+    }
+}
@@ -1,17 +1,33 @@
 use std::{
+    any::Any,
     error::Error,
-    io::{self, stdout, Stdout},
+    io::{self, stdout, Stdout, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
 };
 
 use crossterm::{
+    cursor::Show,
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
 };
 use ratatui::{
+    backend::Backend,
     buffer::Buffer,
     layout::Rect,
     prelude::CrosstermBackend,
     widgets::{StatefulWidget, Widget},
+    Terminal, TerminalOptions, Viewport,
 };
 
 pub type WidgetBackend = CrosstermBackend<Stdout>;
@@ -79,6 +95,11 @@ impl WidgetFrame {
         self.cursor_position = Some((x, y));
     }
 
+    /// Gets the buffer that this `Frame` draws into.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
     /// Gets the buffer that this `Frame` draws into as a mutable reference.
     pub fn buffer_mut(&mut self) -> &mut Buffer {
         &mut self.buffer
@@ -113,29 +134,319 @@ impl WidgetFrame {
     }
 }
 
-/// Sets up the terminal to work with your app
+/// A terminal that the app can draw to, independent of the backend it uses.
+pub(crate) trait AppTerminal {
+    /// Resizes the terminal if needed and creates the frame for the widgets to draw to.
+    fn begin_frame(&mut self) -> io::Result<WidgetFrame>;
+
+    /// Draws the finished frame to the backend.
+    fn end_frame(&mut self, frame: &WidgetFrame) -> io::Result<()>;
+
+    fn hide_cursor(&mut self) -> io::Result<()>;
+
+    fn show_cursor(&mut self) -> io::Result<()>;
+
+    /// Clears the screen, so the next frame is drawn in full.
+    fn clear(&mut self) -> io::Result<()>;
+
+    /// Draws lines above an inline viewport, into the terminal history.
+    /// Does nothing for any other viewport.
+    fn insert_before(&mut self, height: u16, draw: Box<dyn FnOnce(&mut Buffer)>) -> io::Result<()>;
+
+    /// Allows the terminal to be downcast back to the type it was created as.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<B: Backend + 'static> AppTerminal for Terminal<B> {
+    fn begin_frame(&mut self) -> io::Result<WidgetFrame> {
+        self.autoresize()?;
+        let mut frame = self.get_frame();
+
+        Ok(WidgetFrame {
+            cursor_position: None,
+            buffer: frame.buffer_mut().clone(),
+            viewport_area: frame.area(),
+            count: frame.count(),
+        })
+    }
+
+    fn end_frame(&mut self, widget_frame: &WidgetFrame) -> io::Result<()> {
+        *self.current_buffer_mut() = widget_frame.buffer.clone();
+
+        self.flush()?;
+
+        match widget_frame.cursor_position {
+            Some(position) => {
+                self.show_cursor()?;
+                self.set_cursor_position(position)?;
+            }
+            None => self.hide_cursor()?,
+        }
+
+        self.swap_buffers();
+
+        self.backend_mut().flush()
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        Terminal::hide_cursor(self)
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        Terminal::show_cursor(self)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        Terminal::clear(self)
+    }
+
+    fn insert_before(&mut self, height: u16, draw: Box<dyn FnOnce(&mut Buffer)>) -> io::Result<()> {
+        Terminal::insert_before(self, height, draw)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Stands in for the terminal of an app while it is being moved around internally.
+pub(crate) struct DetachedTerminal;
+
+impl AppTerminal for DetachedTerminal {
+    fn begin_frame(&mut self) -> io::Result<WidgetFrame> {
+        Err(io::Error::other("The terminal is detached from the app"))
+    }
+
+    fn end_frame(&mut self, _frame: &WidgetFrame) -> io::Result<()> {
+        Err(io::Error::other("The terminal is detached from the app"))
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn insert_before(
+        &mut self,
+        _height: u16,
+        _draw: Box<dyn FnOnce(&mut Buffer)>,
+    ) -> io::Result<()> {
+        Err(io::Error::other("The terminal is detached from the app"))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// The terminal features an app turns on while it runs, on top of raw mode.
+///
+/// Everything turned on is turned back off whenever the terminal is restored,
+/// such as when the app stops, panics or is suspended.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AppConfig {
+    inline: Option<u16>,
+    mouse_capture: bool,
+    bracketed_paste: bool,
+    focus_change: bool,
+    keyboard_enhancement: Option<KeyboardEnhancementFlags>,
+}
+
+impl AppConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draws in a viewport of the given height below the cursor, instead of the alternate screen,
+    /// so everything drawn is left in the terminal history.
+    pub fn inline(mut self, height: u16) -> Self {
+        self.inline = Some(height);
+        self
+    }
+
+    /// Reports mouse clicks, drags, movement and scrolling as
+    /// [`Event::Mouse`](crossterm::event::Event::Mouse).
+    pub fn mouse_capture(mut self, enabled: bool) -> Self {
+        self.mouse_capture = enabled;
+        self
+    }
+
+    /// Reports pasted text as a single [`Event::Paste`](crossterm::event::Event::Paste),
+    /// instead of a key press for every character.
+    pub fn bracketed_paste(mut self, enabled: bool) -> Self {
+        self.bracketed_paste = enabled;
+        self
+    }
+
+    /// Reports when the terminal gains or loses focus, as
+    /// [`Event::FocusGained`](crossterm::event::Event::FocusGained) and
+    /// [`Event::FocusLost`](crossterm::event::Event::FocusLost).
+    pub fn focus_change(mut self, enabled: bool) -> Self {
+        self.focus_change = enabled;
+        self
+    }
+
+    /// Turns on the kitty keyboard protocol with the given flags, allowing key releases,
+    /// repeats and more modifiers to be reported.
+    /// Nothing is sent to terminals that don't support the protocol.
+    pub fn keyboard_enhancement(mut self, flags: KeyboardEnhancementFlags) -> Self {
+        self.keyboard_enhancement = Some(flags);
+        self
+    }
+
+    /// Returns the height of the inline viewport, if the app draws in one.
+    pub fn inline_height(&self) -> Option<u16> {
+        self.inline
+    }
+}
+
+/// Sets up the terminal to work with your app, turning on everything in the config.
 /// This is run automatically by app.
-pub fn setup_terminal() -> Result<WidgetTerminal, io::Error> {
-    enable_raw_mode()?;
-    let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let terminal = WidgetTerminal::new(backend)?;
+pub fn setup_terminal(config: &AppConfig) -> Result<WidgetTerminal, io::Error> {
+    resume_terminal(config)?;
+    let backend = CrosstermBackend::new(stdout());
+    let viewport = match config.inline {
+        Some(height) => Viewport::Inline(height),
+        None => Viewport::Fullscreen,
+    };
+    let terminal = WidgetTerminal::with_options(backend, TerminalOptions { viewport })?;
     Ok(terminal)
 }
 
 /// Takes down the terminal, ensuring that it is all ok.
-/// This is run automatically by the app.
-pub fn restore_terminal(mut terminal: WidgetTerminal) -> Result<(), io::Error> {
+pub fn restore_terminal(mut terminal: WidgetTerminal, config: &AppConfig) -> Result<(), io::Error> {
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    disable_features(terminal.backend_mut(), config)
+}
+
+/// Puts the terminal back into the state the app needs after [`reset_terminal`],
+/// such as when the app is resumed after being suspended.
+pub fn resume_terminal(config: &AppConfig) -> Result<(), io::Error> {
+    enable_raw_mode()?;
+    enable_features(&mut stdout(), config)
+}
+
+/// Turns on everything in the config, other than raw mode.
+fn enable_features(writer: &mut impl Write, config: &AppConfig) -> Result<(), io::Error> {
+    if config.inline.is_none() {
+        execute!(writer, EnterAlternateScreen)?;
+    }
+    if config.mouse_capture {
+        execute!(writer, EnableMouseCapture)?;
+    }
+    if config.bracketed_paste {
+        execute!(writer, EnableBracketedPaste)?;
+    }
+    if config.focus_change {
+        execute!(writer, EnableFocusChange)?;
+    }
+    if let Some(flags) = config.keyboard_enhancement {
+        if keyboard_enhancement_supported() {
+            execute!(writer, PushKeyboardEnhancementFlags(flags))?;
+            KEYBOARD_ENHANCED.store(true, Ordering::Release);
+        }
+    }
     Ok(())
 }
 
-/// Resets the terminal in case of a panic.
+/// Whether the kitty keyboard protocol flags were pushed, so they are only popped if they were.
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Returns whether the terminal supports the kitty keyboard protocol.
+/// Only asked once, as the answer takes a round trip through the terminal.
+fn keyboard_enhancement_supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+
+    *SUPPORTED.get_or_init(|| supports_keyboard_enhancement().unwrap_or(false))
+}
+
+/// Resets the terminal in case of a panic, or once an app that set the terminal up finishes,
+/// turning off everything in the config.
 /// This is handled automatically if panic handler is enabled.
-pub fn reset_terminal() -> Result<(), io::Error> {
+pub fn reset_terminal(config: &AppConfig) -> Result<(), io::Error> {
     disable_raw_mode()?;
-    execute!(stdout(), LeaveAlternateScreen)?;
+    disable_features(&mut stdout(), config)
+}
+
+/// Turns off everything [`enable_features`] turned on, in the opposite order.
+fn disable_features(writer: &mut impl Write, config: &AppConfig) -> Result<(), io::Error> {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::AcqRel) {
+        execute!(writer, PopKeyboardEnhancementFlags)?;
+    }
+    if config.focus_change {
+        execute!(writer, DisableFocusChange)?;
+    }
+    if config.bracketed_paste {
+        execute!(writer, DisableBracketedPaste)?;
+    }
+    if config.mouse_capture {
+        execute!(writer, DisableMouseCapture)?;
+    }
+
+    match config.inline {
+        // Leave what was drawn in place, moving onto a new line ready for the shell.
+        Some(_) => {
+            execute!(writer, Show)?;
+            writeln!(writer)?;
+        }
+        None => execute!(writer, LeaveAlternateScreen)?,
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use crossterm::{
+        cursor::Show,
+        event::{
+            DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        },
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+    };
+
+    use super::{disable_features, enable_features, AppConfig};
+
+    #[test]
+    fn only_configured_features_are_toggled() {
+        let config = AppConfig::new().mouse_capture(true).bracketed_paste(true);
+
+        let mut enabled = vec![];
+        enable_features(&mut enabled, &config).unwrap();
+        let mut expected = vec![];
+        execute!(
+            expected,
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableBracketedPaste
+        )
+        .unwrap();
+        assert_eq!(enabled, expected);
+
+        let mut disabled = vec![];
+        disable_features(&mut disabled, &config).unwrap();
+        let mut expected = vec![];
+        execute!(
+            expected,
+            DisableBracketedPaste,
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        )
+        .unwrap();
+        assert_eq!(disabled, expected);
+
+        let mut disabled = vec![];
+        disable_features(&mut disabled, &AppConfig::new().inline(3)).unwrap();
+        let mut expected = vec![];
+        execute!(expected, Show).unwrap();
+        expected.push(b'\n');
+        assert_eq!(disabled, expected);
+    }
+}
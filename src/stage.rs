@@ -0,0 +1,207 @@
+/// The stages that widgets are registered to, which decide when they run.
+///
+/// Each frame runs the widgets of every stage in the order they are declared here,
+/// with widgets in the same stage running in the order they were added,
+/// unless ordering constraints such as `before` and `after` say otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    /// Runs a single time, before the first frame.
+    Startup,
+    /// Runs before updating, useful for generating chunks and reading input.
+    PreUpdate,
+    /// Runs at a fixed rate set by [`FixedTime`](crate::states::FixedTime),
+    /// which may be any number of times per frame.
+    FixedUpdate,
+    /// The default stage, where the logic of the app should live.
+    Update,
+    /// Runs after updating, where everything should be drawn.
+    Render,
+    /// Runs after everything has been drawn for the frame.
+    PostRender,
+    /// Runs a single time once the app stops, whether a widget exited, a widget errored
+    /// or the app was stopped by Ctrl-C, `SIGINT`, `SIGTERM` or `SIGHUP`,
+    /// before the terminal is restored. See [`App::ctrl_c_exits`](crate::App::ctrl_c_exits).
+    Shutdown,
+}
+
+impl Stage {
+    /// The stages that run every frame, in order.
+    pub const FRAME: [Stage; 5] = [
+        Stage::PreUpdate,
+        Stage::FixedUpdate,
+        Stage::Update,
+        Stage::Render,
+        Stage::PostRender,
+    ];
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        states::FixedTime,
+        testing::{AppHarness, Log},
+        widget::WidgetError,
+        *,
+    };
+
+    use super::Stage;
+
+    fn startup(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("startup");
+        Ok(())
+    }
+
+    fn pre_update(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("pre_update");
+        Ok(())
+    }
+
+    fn update(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("update");
+        Ok(())
+    }
+
+    fn render(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("render");
+        Ok(())
+    }
+
+    fn post_render(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("post_render");
+        Ok(())
+    }
+
+    #[test]
+    fn stages_run_in_order() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .add_widgets(Stage::PostRender, post_render)
+                .add_widgets(Stage::Render, render)
+                .widgets(update)
+                .add_widgets(Stage::PreUpdate, pre_update)
+                .add_widgets(Stage::Startup, startup)
+        });
+
+        harness.step_n(2).unwrap();
+
+        assert_eq!(
+            harness.log().entries(),
+            [
+                "startup",
+                "pre_update",
+                "update",
+                "render",
+                "post_render",
+                "pre_update",
+                "update",
+                "render",
+                "post_render"
+            ]
+        );
+    }
+
+    #[derive(Default, State)]
+    struct Config {
+        width: Option<u16>,
+    }
+
+    fn load_config(frame: Res<WidgetFrame>, mut config: ResMut<Config>) -> WidgetResult {
+        config.width = Some(frame.size().width);
+        Ok(())
+    }
+
+    fn failing_startup() -> WidgetResult {
+        Err(anyhow!("config missing").into())
+    }
+
+    #[test]
+    fn startup_runs_once() {
+        let mut harness = AppHarness::new(12, 1).app(|app| {
+            app.states(Log::default())
+                .states(Config::default())
+                .startup_widgets((load_config, startup))
+                .widgets(update)
+        });
+
+        harness.step_n(3).unwrap();
+
+        assert_eq!(harness.state::<Config>().width, Some(12));
+        assert_eq!(
+            harness.log().entries(),
+            ["startup", "update", "update", "update"]
+        );
+    }
+
+    #[test]
+    fn startup_error_aborts() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .startup_widgets(failing_startup)
+                .widgets(update)
+        });
+
+        assert!(harness.step().is_err());
+        assert!(harness.log().is_empty());
+    }
+
+    #[derive(Default, State)]
+    struct Ticks(u32);
+
+    fn tick(mut ticks: ResMut<Ticks>) -> WidgetResult {
+        ticks.0 += 1;
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_update_runs_per_timestep() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Ticks::default())
+                .fixed_timestep(Duration::from_millis(10))
+                .add_widgets(Stage::FixedUpdate, tick)
+        });
+
+        harness.set_frame_time(Duration::from_millis(25));
+        harness.step().unwrap();
+        assert_eq!(harness.state::<Ticks>().0, 2);
+
+        harness.step().unwrap();
+        assert_eq!(harness.state::<Ticks>().0, 5);
+
+        harness.set_frame_time(Duration::from_millis(4));
+        harness.step().unwrap();
+        assert_eq!(harness.state::<Ticks>().0, 5);
+        assert_eq!(
+            harness.state::<FixedTime>().accumulated(),
+            Duration::from_millis(4)
+        );
+    }
+
+    #[derive(Default, State)]
+    struct Saved(bool);
+
+    fn fail() -> WidgetResult {
+        Err(WidgetError::ChunkError)
+    }
+
+    fn save(mut saved: ResMut<Saved>) -> WidgetResult {
+        saved.0 = true;
+        Ok(())
+    }
+
+    #[test]
+    fn shutdown_runs_after_errors() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Saved::default())
+                .widgets(fail)
+                .shutdown_widgets(save)
+        });
+
+        assert!(harness.step().is_err());
+        assert!(!harness.state::<Saved>().0);
+
+        harness.shutdown().unwrap();
+        assert!(harness.state::<Saved>().0);
+    }
+}
@@ -54,17 +54,181 @@ impl_multi_from_states! { A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9}
 // ---------- Guarenteed States --------- //
 
 /// The state that will store anything time related for the system.
+///
+/// Measured by the app around each whole frame, so it includes the time widgets take to run.
 #[derive(Default, Clone, State)]
 pub struct Time {
-    frame_duration: Duration,
+    delta: Duration,
+    elapsed: Duration,
+    frame_count: usize,
+    smoothed_delta: Option<Duration>,
 }
 
 impl Time {
+    /// How much the smoothed delta moves towards the latest delta each frame.
+    const SMOOTHING: f64 = 0.1;
+
+    /// Moves time forward by a single frame.
+    pub(crate) fn tick(&mut self, delta: Duration, frame_count: usize) {
+        self.delta = delta;
+        self.elapsed += delta;
+        self.frame_count = frame_count;
+
+        if delta.is_zero() {
+            return;
+        }
+
+        self.smoothed_delta = Some(match self.smoothed_delta {
+            Some(smoothed) => {
+                smoothed.mul_f64(1.0 - Self::SMOOTHING) + delta.mul_f64(Self::SMOOTHING)
+            }
+            None => delta,
+        });
+    }
+
+    /// Overrides the delta of the current frame.
     pub fn set_duration(&mut self, duration: Duration) {
-        self.frame_duration = duration
+        self.delta = duration
     }
 
+    /// The real time between the start of the last frame and the start of this one.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// The same as [`Time::delta`].
     pub fn frame_time(&self) -> Duration {
-        self.frame_duration
+        self.delta
+    }
+
+    /// The total time since the first frame.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The index of the current frame, matching [`WidgetFrame::count`](crate::WidgetFrame::count).
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// The frames per second, smoothed over recent frames so it doesn't jump around.
+    /// Returns `0.0` until a frame with a measurable delta has run.
+    pub fn fps(&self) -> f64 {
+        match self.smoothed_delta {
+            Some(smoothed) => 1.0 / smoothed.as_secs_f64(),
+            None => 0.0,
+        }
+    }
+}
+
+/// The state that controls how often the [`Stage::FixedUpdate`](crate::Stage::FixedUpdate)
+/// stage runs, independent of how often frames are drawn.
+///
+/// Real time is accumulated every frame, and the stage runs once for every whole timestep
+/// that has built up, so it may run several times in one frame, or not at all.
+#[derive(Clone, State)]
+pub struct FixedTime {
+    timestep: Duration,
+    accumulated: Duration,
+    max_steps: u32,
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1) / 60)
+    }
+}
+
+impl FixedTime {
+    /// Creates a fixed time that runs once every `timestep`.
+    pub fn new(timestep: Duration) -> Self {
+        Self {
+            timestep,
+            accumulated: Duration::ZERO,
+            max_steps: 8,
+        }
+    }
+
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    pub fn set_timestep(&mut self, timestep: Duration) {
+        self.timestep = timestep
+    }
+
+    /// Sets the most times the stage can run in a single frame.
+    /// Any time beyond that is dropped, so a slow frame can't cause a growing backlog.
+    pub fn set_max_steps(&mut self, max_steps: u32) {
+        self.max_steps = max_steps
+    }
+
+    /// The time that has built up towards the next step.
+    pub fn accumulated(&self) -> Duration {
+        self.accumulated
+    }
+
+    /// How far through the next step the accumulated time is, from `0.0` to `1.0`.
+    /// Useful for smoothly drawing values between steps.
+    pub fn overstep_fraction(&self) -> f32 {
+        if self.timestep.is_zero() {
+            return 0.0;
+        }
+
+        self.accumulated.as_secs_f32() / self.timestep.as_secs_f32()
+    }
+
+    /// How long until enough time has built up for the next step,
+    /// or `None` if a zero timestep means it never steps.
+    pub(crate) fn until_next_step(&self) -> Option<Duration> {
+        if self.timestep.is_zero() {
+            return None;
+        }
+
+        Some(self.timestep.saturating_sub(self.accumulated))
+    }
+
+    /// Adds the time since the last frame, returning how many steps should run.
+    pub(crate) fn accumulate(&mut self, delta: Duration) -> u32 {
+        if self.timestep.is_zero() {
+            return 0;
+        }
+
+        self.accumulated += delta;
+
+        let mut steps = 0;
+        while self.accumulated >= self.timestep && steps < self.max_steps {
+            self.accumulated -= self.timestep;
+            steps += 1;
+        }
+
+        if self.accumulated >= self.timestep {
+            let remainder = self.accumulated.as_nanos() % self.timestep.as_nanos();
+            self.accumulated = Duration::from_nanos(remainder as u64);
+        }
+
+        steps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::testing::AppHarness;
+
+    use super::Time;
+
+    #[test]
+    fn time_tracks_every_frame() {
+        let mut harness = AppHarness::new(1, 1);
+        harness.set_frame_time(Duration::from_millis(20));
+        harness.step_n(3).unwrap();
+
+        let time = harness.state::<Time>();
+        assert_eq!(time.delta(), Duration::from_millis(20));
+        assert_eq!(time.elapsed(), Duration::from_millis(60));
+        assert_eq!(time.frame_count(), harness.frame().count());
+        assert!((time.fps() - 50.0).abs() < 0.001);
     }
 }
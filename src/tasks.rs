@@ -0,0 +1,470 @@
+use std::{
+    cell::{Cell, OnceCell},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use crate::{redraw::RedrawHandle, states::States, Commands, State, WidgetParam};
+
+/// Work that runs on the app thread once a task has finished.
+pub(crate) type Completion = Box<dyn FnOnce(&States) + Send>;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A finished task, along with the generation of tasks it was spawned in.
+/// `None` is sent when a task panicked, so it is still counted as done.
+type Finished = (u64, Option<Completion>);
+
+thread_local! {
+    static IN_TASK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns whether the current thread is one that tasks run on,
+/// so a panicking task doesn't reset the terminal the app is still drawing to.
+pub(crate) fn in_task() -> bool {
+    IN_TASK.get()
+}
+
+fn mark_task_thread() {
+    IN_TASK.set(true);
+}
+
+/// A fixed number of threads that run jobs in the order they were queued.
+struct ThreadPool {
+    jobs: Sender<Job>,
+}
+
+impl ThreadPool {
+    fn new(threads: usize) -> Self {
+        let (jobs, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..threads {
+            let receiver = receiver.clone();
+
+            thread::spawn(move || {
+                mark_task_thread();
+
+                loop {
+                    let job = receiver.lock().map(|receiver| receiver.recv());
+
+                    // The pool was dropped, so no more jobs will arrive.
+                    let Ok(Ok(job)) = job else {
+                        return;
+                    };
+
+                    job();
+                }
+            });
+        }
+
+        Self { jobs }
+    }
+}
+
+/// The state used to run slow work off of the render loop.
+///
+/// Each task runs on a background thread, then its result is handed to a callback
+/// on the app thread at the start of the next frame, waking the app if it is
+/// in [`RunMode::Reactive`](crate::RunMode::Reactive).
+///
+#[cfg_attr(
+    feature = "tokio",
+    doc = "Futures can be spawned onto a tokio runtime in the same way with [`Tasks::spawn_async`]."
+)]
+#[cfg_attr(
+    not(feature = "tokio"),
+    doc = "With the `tokio` feature enabled, futures can be spawned onto a tokio runtime \
+           in the same way with `Tasks::spawn_async`."
+)]
+///
+/// Pending tasks are cancelled with [`Tasks::cancel_all`], and once the tasks are dropped,
+/// such as when the app stops.
+#[derive(State)]
+pub struct Tasks {
+    pool: OnceCell<ThreadPool>,
+    #[cfg(feature = "tokio")]
+    runtime: OnceCell<AsyncRuntime>,
+    #[cfg(feature = "tokio")]
+    futures: Vec<tokio::task::AbortHandle>,
+    sender: Sender<Finished>,
+    receiver: Receiver<Finished>,
+    /// Counts up whenever the tasks are cancelled, so tasks from before then are ignored.
+    generation: Arc<AtomicU64>,
+    pending: usize,
+    redraw: RedrawHandle,
+}
+
+impl Tasks {
+    pub(crate) fn new(redraw: RedrawHandle) -> Self {
+        let (sender, receiver) = channel();
+
+        Self {
+            pool: OnceCell::new(),
+            #[cfg(feature = "tokio")]
+            runtime: OnceCell::new(),
+            #[cfg(feature = "tokio")]
+            futures: vec![],
+            sender,
+            receiver,
+            generation: Arc::default(),
+            pending: 0,
+            redraw,
+        }
+    }
+
+    /// Runs `work` on a background thread, then passes its result to `then`
+    /// on the app thread, where it can change any state.
+    ///
+    /// If `work` panics, `then` is never called.
+    pub fn spawn<T, W, C>(&mut self, work: W, then: C)
+    where
+        T: Send + 'static,
+        W: FnOnce() -> T + Send + 'static,
+        C: FnOnce(T, &States) + Send + 'static,
+    {
+        let complete = self.completer(then);
+        let cancelled = self.cancelled();
+
+        let pool = self.pool.get_or_init(|| {
+            ThreadPool::new(thread::available_parallelism().map_or(4, |threads| threads.get()))
+        });
+
+        let _ = pool.jobs.send(Box::new(move || {
+            // Cancelled before it started, so there is nothing to hand back.
+            if cancelled() {
+                return;
+            }
+
+            complete(catch_unwind(AssertUnwindSafe(work)).ok())
+        }));
+    }
+
+    /// Runs `work` on a background thread, then inserts its result as a state,
+    /// replacing any state of the same type.
+    pub fn spawn_state<T, W>(&mut self, work: W)
+    where
+        T: State + Send + 'static,
+        W: FnOnce() -> T + Send + 'static,
+    {
+        self.spawn(work, insert_state);
+    }
+
+    /// Runs `future` on a tokio runtime, then passes its output to `then`
+    /// on the app thread, where it can change any state.
+    ///
+    /// If the app is running within a tokio runtime, the future is spawned onto it,
+    /// otherwise a runtime is created the first time a future is spawned.
+    #[cfg(feature = "tokio")]
+    pub fn spawn_async<T, F, C>(&mut self, future: F, then: C)
+    where
+        T: Send + 'static,
+        F: std::future::Future<Output = T> + Send + 'static,
+        C: FnOnce(T, &States) + Send + 'static,
+    {
+        let complete = self.completer(then);
+
+        let runtime = self.runtime.get_or_init(AsyncRuntime::new).handle();
+
+        let mut future = Box::pin(future);
+        let task = runtime.spawn(std::future::poll_fn(move |context| {
+            // Marked while polling, as a shared runtime may run futures on the app thread.
+            let _marked = MarkedTask::enter();
+            future.as_mut().poll(context)
+        }));
+
+        self.futures.retain(|future| !future.is_finished());
+        self.futures.push(task.abort_handle());
+
+        runtime.spawn(async move {
+            complete(task.await.ok());
+        });
+    }
+
+    /// Cancels every pending task, so none of their results are handed back to the app.
+    ///
+    /// Tasks that haven't started are skipped and futures are aborted at their next `.await`,
+    /// but work already running on a thread still runs to the end, with its result dropped.
+    pub fn cancel_all(&mut self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.pending = 0;
+
+        // Drops anything that finished before being cancelled.
+        for _ in self.receiver.try_iter() {}
+
+        #[cfg(feature = "tokio")]
+        for future in self.futures.drain(..) {
+            future.abort();
+        }
+    }
+
+    /// Returns how many tasks have been spawned, but not handed back to the app yet.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Creates the function that sends a finished task back to the app, waking it up.
+    fn completer<T: Send + 'static>(
+        &mut self,
+        then: impl FnOnce(T, &States) + Send + 'static,
+    ) -> impl FnOnce(Option<T>) + Send + 'static {
+        self.pending += 1;
+
+        let sender = self.sender.clone();
+        let redraw = self.redraw.clone();
+        let generation = self.generation.load(Ordering::Acquire);
+
+        move |result| {
+            let completion: Option<Completion> = match result {
+                Some(result) => Some(Box::new(move |states: &States| then(result, states))),
+                None => None,
+            };
+
+            // The app has already closed if this fails, so there is nothing left to update.
+            let _ = sender.send((generation, completion));
+            redraw.request_redraw();
+        }
+    }
+
+    /// Returns a check for whether the tasks spawned so far have been cancelled.
+    fn cancelled(&self) -> impl Fn() -> bool + Send + 'static {
+        let generation = self.generation.clone();
+        let spawned = generation.load(Ordering::Acquire);
+
+        move || generation.load(Ordering::Acquire) != spawned
+    }
+
+    /// Takes every task that has finished since the last frame,
+    /// ignoring those that were cancelled.
+    pub(crate) fn take_completed(&mut self) -> Vec<Completion> {
+        let generation = self.generation.load(Ordering::Acquire);
+
+        let completed = self
+            .receiver
+            .try_iter()
+            .filter(|(spawned, _)| *spawned == generation)
+            .map(|(_, completion)| completion)
+            .collect::<Vec<_>>();
+        self.pending -= completed.len();
+
+        completed.into_iter().flatten().collect()
+    }
+}
+
+impl Drop for Tasks {
+    fn drop(&mut self) {
+        self.cancel_all();
+
+        // Blocking on the workers would panic if the app is dropped within another runtime.
+        #[cfg(feature = "tokio")]
+        if let Some(AsyncRuntime::Owned(runtime)) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// The tokio runtime futures are spawned onto.
+#[cfg(feature = "tokio")]
+enum AsyncRuntime {
+    /// The runtime the app was running within when the first future was spawned.
+    Shared(tokio::runtime::Handle),
+    /// A runtime created for the app, as it wasn't running within one.
+    Owned(tokio::runtime::Runtime),
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncRuntime {
+    fn new() -> Self {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => Self::Shared(handle),
+            Err(_) => Self::Owned(
+                tokio::runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .expect("The tokio runtime should be able to start"),
+            ),
+        }
+    }
+
+    fn handle(&self) -> &tokio::runtime::Handle {
+        match self {
+            Self::Shared(handle) => handle,
+            Self::Owned(runtime) => runtime.handle(),
+        }
+    }
+}
+
+/// Marks the current thread as running a task until dropped,
+/// restoring the previous mark even if the task panics.
+#[cfg(feature = "tokio")]
+struct MarkedTask(bool);
+
+#[cfg(feature = "tokio")]
+impl MarkedTask {
+    fn enter() -> Self {
+        Self(IN_TASK.replace(true))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for MarkedTask {
+    fn drop(&mut self) {
+        IN_TASK.set(self.0);
+    }
+}
+
+fn insert_state<T: State + 'static>(state: T, states: &States) {
+    Commands::retrieve(states).insert_states(state);
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use crate::{testing::AppHarness, *};
+
+    use super::Tasks;
+
+    #[derive(State)]
+    struct Loaded(u64);
+
+    #[derive(Default, State)]
+    struct Total(u64);
+
+    fn load(mut tasks: ResMut<Tasks>) -> WidgetResult {
+        tasks.spawn_state(|| Loaded((1..=10).sum()));
+        tasks.spawn(
+            || 32,
+            |value, states| ResMut::<Total>::retrieve(states).0 += value,
+        );
+        tasks.spawn(|| -> u64 { panic!("Task failed") }, |_, _| unreachable!());
+        Ok(())
+    }
+
+    #[test]
+    fn results_are_delivered_to_states() {
+        let mut harness =
+            AppHarness::new(1, 1).app(|app| app.states(Total::default()).startup_widgets(load));
+
+        harness.step().unwrap();
+
+        for _ in 0..500 {
+            if harness.state::<Tasks>().pending() == 0 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(2));
+            harness.step().unwrap();
+        }
+
+        // Commands queued by a task are applied before the widgets of the frame it is delivered in.
+        assert_eq!(harness.state::<Tasks>().pending(), 0);
+        assert_eq!(harness.state::<Loaded>().0, 55);
+        assert_eq!(harness.state::<Total>().0, 32);
+    }
+
+    #[test]
+    fn cancelled_tasks_are_never_delivered() {
+        let mut harness = AppHarness::new(1, 1).app(|app| app.states(Total::default()));
+
+        {
+            let mut tasks = harness.state_mut::<Tasks>();
+            tasks.spawn(
+                || {
+                    thread::sleep(Duration::from_millis(10));
+                    5
+                },
+                |value, states| ResMut::<Total>::retrieve(states).0 += value,
+            );
+            tasks.cancel_all();
+        }
+
+        thread::sleep(Duration::from_millis(30));
+        harness.step_n(2).unwrap();
+
+        assert_eq!(harness.state::<Tasks>().pending(), 0);
+        assert_eq!(harness.state::<Total>().0, 0);
+    }
+
+    #[derive(Default, State)]
+    struct RanInTask(bool);
+
+    #[test]
+    fn task_threads_are_marked() {
+        let mut harness = AppHarness::new(1, 1).app(|app| app.states(RanInTask::default()));
+
+        harness
+            .state_mut::<Tasks>()
+            .spawn(super::in_task, |in_task, states| {
+                ResMut::<RanInTask>::retrieve(states).0 = in_task
+            });
+
+        for _ in 0..500 {
+            if harness.state::<Tasks>().pending() == 0 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(2));
+            harness.step().unwrap();
+        }
+
+        assert!(harness.state::<RanInTask>().0);
+        assert!(!super::in_task());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn futures_are_delivered_to_states() {
+        let mut harness = AppHarness::new(1, 1).app(|app| app.states(Total::default()));
+
+        harness
+            .state_mut::<Tasks>()
+            .spawn_async(async { 7 }, |value, states| {
+                ResMut::<Total>::retrieve(states).0 += value
+            });
+
+        for _ in 0..500 {
+            if harness.state::<Tasks>().pending() == 0 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(2));
+            harness.step().unwrap();
+        }
+
+        assert_eq!(harness.state::<Total>().0, 7);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn apps_can_run_within_a_tokio_runtime() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let mut harness = AppHarness::new(1, 1).app(|app| app.states(Total::default()));
+
+            harness
+                .state_mut::<Tasks>()
+                .spawn_async(async { 7 }, |value, states| {
+                    ResMut::<Total>::retrieve(states).0 += value
+                });
+
+            for _ in 0..500 {
+                if harness.state::<Tasks>().pending() == 0 {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(2));
+                harness.step().unwrap();
+            }
+
+            assert_eq!(harness.state::<Total>().0, 7);
+
+            // The app, along with its tasks, is dropped while still within the runtime.
+        });
+    }
+}
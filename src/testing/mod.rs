@@ -0,0 +1,241 @@
+pub mod snapshot;
+
+use std::{any::TypeId, collections::VecDeque, time::Duration};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    backend::{Backend, TestBackend},
+    Terminal,
+};
+
+use crate::{stage::Stage, App, Res, ResMut, State, WidgetFrame, WidgetParam, WidgetResult};
+
+/// A state that records what happened in this crate's own tests,
+/// such as which widgets ran and in what order.
+#[cfg(test)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, State)]
+pub(crate) struct Log(Vec<String>);
+
+#[cfg(test)]
+impl Log {
+    pub fn push(&mut self, entry: impl Into<String>) {
+        self.0.push(entry.into());
+    }
+
+    /// Returns every entry, in the order they were pushed.
+    pub fn entries(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Runs an app without a real terminal, allowing tests to step through frames by hand.
+///
+/// Widgets, states and sets are registered on the [`App`] through [`AppHarness::app`],
+/// then events can be pushed and frames stepped, with the resulting frame and any
+/// state being available to inspect between steps.
+pub struct AppHarness {
+    app: App,
+    events: VecDeque<Vec<Event>>,
+    frame_time: Duration,
+}
+
+impl AppHarness {
+    /// Create a new harness that draws to a [`TestBackend`] of the given size.
+    pub fn new(width: u16, height: u16) -> Self {
+        let terminal = Terminal::new(TestBackend::new(width, height))
+            .expect("The test backend should never fail to create a terminal");
+
+        Self::from_app(App::from_terminal(terminal, 0))
+    }
+
+    /// Create a new harness from an already built app.
+    pub fn from_app(app: App) -> Self {
+        Self {
+            app,
+            events: VecDeque::new(),
+            frame_time: Duration::ZERO,
+        }
+    }
+
+    /// Changes the app being tested, using any of the builder methods of [`App`].
+    ///
+    /// ```
+    /// # use widgetui::{testing::AppHarness, *};
+    /// # fn draw() -> WidgetResult { Ok(()) }
+    /// let harness = AppHarness::new(10, 2).app(|app| app.widgets(draw));
+    /// ```
+    pub fn app(mut self, build: impl FnOnce(App) -> App) -> Self {
+        self.app = build(self.app);
+        self
+    }
+
+    /// Returns the app being tested, for anything that isn't a builder method,
+    /// such as [`App::event_sender`].
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Sets the time that passes between steps, as reported by the [`Time`](crate::states::Time) state.
+    pub fn set_frame_time(&mut self, frame_time: Duration) {
+        self.frame_time = frame_time;
+    }
+
+    /// Queues an event, which will be passed to the widgets on a future step.
+    /// Each step consumes one queued batch, with this event being a batch of its own.
+    pub fn push_event(&mut self, event: Event) {
+        self.push_events([event]);
+    }
+
+    /// Queues a batch of events, which will all be passed to the widgets on the same step,
+    /// like a burst of input arriving between two frames.
+    pub fn push_events(&mut self, events: impl IntoIterator<Item = Event>) {
+        self.events.push_back(events.into_iter().collect());
+    }
+
+    /// Queues a key press without any modifiers.
+    pub fn push_key(&mut self, code: KeyCode) {
+        self.push_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+
+    /// Runs a single frame of the app, returning an error if any of the widgets error out.
+    /// The first step also runs the [`Stage::Startup`] widgets.
+    pub fn step(&mut self) -> WidgetResult {
+        self.app.startup()?;
+
+        let events = self.events.pop_front().unwrap_or_default();
+        self.app.frame(events, self.frame_time)
+    }
+
+    /// Runs the given number of frames, stopping at the first error.
+    pub fn step_n(&mut self, frames: usize) -> WidgetResult {
+        for _ in 0..frames {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the frame that was drawn by the last step.
+    ///
+    /// # Panics
+    /// Panics if no frame has been stepped yet.
+    pub fn frame(&self) -> Res<'_, WidgetFrame> {
+        self.state::<WidgetFrame>()
+    }
+
+    /// Returns the backend the app draws to, if it is a `B`,
+    /// such as to check what an inline app printed above its viewport.
+    pub fn backend<B: Backend + 'static>(&self) -> Option<&B> {
+        self.app.backend()
+    }
+
+    /// Returns the [`Log`] of the app.
+    #[cfg(test)]
+    pub(crate) fn log(&self) -> Res<'_, Log> {
+        self.state::<Log>()
+    }
+
+    /// Returns the given state.
+    ///
+    /// # Panics
+    /// Panics if the state has not been registered.
+    pub fn state<T: 'static>(&self) -> Res<'_, T> {
+        self.app.state()
+    }
+
+    /// Returns the given state mutably, allowing tests to set up scenarios between steps.
+    ///
+    /// # Panics
+    /// Panics if the state has not been registered.
+    pub fn state_mut<T: 'static>(&self) -> ResMut<'_, T> {
+        self.app.state_mut()
+    }
+
+    /// Takes the value given to [`Events::exit_with`](crate::Events::exit_with), if it is a `T`.
+    pub fn take_exit_value<T: 'static>(&mut self) -> Option<T> {
+        self.app.take_exit_value()
+    }
+
+    /// Runs the shutdown widgets, as if the app had stopped running.
+    pub fn shutdown(&mut self) -> WidgetResult {
+        self.app.shutdown()
+    }
+
+    /// Runs the cleanup of every set, as if the app had stopped running.
+    pub fn cleanup(&mut self) {
+        self.app.cleanup();
+    }
+
+    /// Returns whether a widget has registered an exit.
+    pub fn exited(&self) -> bool {
+        self.app.exit_requested()
+    }
+
+    /// Compares the last frame against the plain text snapshot with the given name.
+    /// See [`snapshot::assert_snapshot`] for how snapshots are stored.
+    pub fn assert_snapshot(&self, name: &str) {
+        snapshot::assert_snapshot(name, self.frame().buffer());
+    }
+
+    /// Compares the last frame against the styled snapshot with the given name.
+    pub fn assert_styled_snapshot(&self, name: &str) {
+        snapshot::assert_styled_snapshot(name, self.frame().buffer());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::KeyCode;
+    use ratatui::{buffer::Buffer, widgets::Paragraph};
+
+    use crate::*;
+
+    use super::AppHarness;
+
+    #[derive(Default, State)]
+    struct Counter(u32);
+
+    fn counter(
+        mut frame: ResMut<WidgetFrame>,
+        mut events: ResMut<Events>,
+        mut counter: ResMut<Counter>,
+    ) -> WidgetResult {
+        if events.key(KeyCode::Char('+')) {
+            counter.0 += 1;
+        }
+
+        if events.key(KeyCode::Char('q')) {
+            events.register_exit();
+        }
+
+        let size = frame.size();
+        frame.render_widget(Paragraph::new(format!("Count: {}", counter.0)), size);
+
+        Ok(())
+    }
+
+    #[test]
+    fn step_with_events() {
+        let mut harness =
+            AppHarness::new(10, 1).app(|app| app.widgets(counter).states(Counter::default()));
+
+        harness.push_key(KeyCode::Char('+'));
+        harness.push_key(KeyCode::Char('+'));
+        harness.step_n(3).unwrap();
+
+        assert_eq!(harness.state::<Counter>().0, 2);
+        assert_eq!(
+            *harness.frame().buffer(),
+            Buffer::with_lines(["Count: 2  "])
+        );
+        assert!(!harness.exited());
+
+        harness.push_key(KeyCode::Char('q'));
+        harness.step().unwrap();
+
+        assert!(harness.exited());
+    }
+}
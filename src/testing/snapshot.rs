@@ -0,0 +1,341 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// The environment variable that, when set to `1`, overwrites stored snapshots
+/// with the current output instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "WIDGETUI_UPDATE_SNAPSHOTS";
+
+/// The most differing cells that will be listed in a failed snapshot report.
+const MAX_REPORTED_CELLS: usize = 32;
+
+/// How much of a buffer a snapshot records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// Only the symbols of each cell.
+    Plain,
+    /// The symbols of each cell, followed by every run of non-default styling.
+    Styled,
+}
+
+/// Renders the symbols of a buffer to text, with each row wrapped in `|` so that
+/// trailing whitespace survives editors and diffs.
+pub fn buffer_to_string(buffer: &Buffer) -> String {
+    rows(buffer)
+        .map(|row| format!("|{}|\n", row_symbols(row)))
+        .collect()
+}
+
+/// Renders a buffer to text like [`buffer_to_string`], followed by one line for every
+/// horizontal run of cells that have a non-default style.
+pub fn buffer_to_styled_string(buffer: &Buffer) -> String {
+    let mut result = buffer_to_string(buffer);
+
+    for (y, row) in rows(buffer).enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            let start = x;
+            while x < row.len() && same_style(&row[start], &row[x]) {
+                x += 1;
+            }
+
+            if let Some(style) = describe_style(&row[start]) {
+                result.push_str(&format!("style {y}:{start}..{x} {style}\n"));
+            }
+        }
+    }
+
+    result
+}
+
+/// Compares a buffer against the plain text snapshot with the given name, panicking with
+/// a cell level report if they differ.
+///
+/// Snapshots are stored as `snapshots/<name>.snap` within the crate being tested.
+/// Setting [`UPDATE_SNAPSHOTS_VAR`] to `1` writes every snapshot instead of comparing it,
+/// which is also the only way a new snapshot is created, so a missing snapshot fails.
+pub fn assert_snapshot(name: &str, buffer: &Buffer) {
+    assert_snapshot_with(name, buffer, SnapshotFormat::Plain)
+}
+
+/// Compares a buffer against the styled snapshot with the given name.
+/// See [`assert_snapshot`] for how snapshots are stored.
+pub fn assert_styled_snapshot(name: &str, buffer: &Buffer) {
+    assert_snapshot_with(name, buffer, SnapshotFormat::Styled)
+}
+
+/// Compares a buffer against the snapshot with the given name, using the given format.
+pub fn assert_snapshot_with(name: &str, buffer: &Buffer, format: SnapshotFormat) {
+    let actual = match format {
+        SnapshotFormat::Plain => buffer_to_string(buffer),
+        SnapshotFormat::Styled => buffer_to_styled_string(buffer),
+    };
+
+    let path = snapshot_path(name);
+
+    let update = env::var(UPDATE_SNAPSHOTS_VAR).is_ok_and(|value| value == "1");
+
+    if update {
+        write_snapshot(&path, &actual);
+        return;
+    }
+
+    if !path.exists() {
+        panic!(
+            "Snapshot `{name}` is missing from `{}`\nFound:\n{}Rerun with `{UPDATE_SNAPSHOTS_VAR}=1` to create it.",
+            path.display(),
+            indent(&actual)
+        );
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read snapshot `{}`: {e}", path.display()));
+
+    if let Some(report) = diff_snapshots(&expected, &actual) {
+        panic!(
+            "Snapshot `{name}` does not match `{}`\n{report}\nRerun with `{UPDATE_SNAPSHOTS_VAR}=1` to accept the new output.",
+            path.display()
+        );
+    }
+}
+
+/// Compares two rendered snapshots, returning a readable report of every difference.
+pub fn diff_snapshots(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+
+    let (expected_rows, expected_styles) = split_snapshot(expected);
+    let (actual_rows, actual_styles) = split_snapshot(actual);
+
+    let mut report = String::new();
+
+    let expected_size = grid_size(&expected_rows);
+    let actual_size = grid_size(&actual_rows);
+
+    if expected_size != actual_size {
+        report.push_str(&format!(
+            "Size differs: expected {}x{}, found {}x{}\n",
+            expected_size.0, expected_size.1, actual_size.0, actual_size.1
+        ));
+    } else {
+        let differing = expected_rows
+            .iter()
+            .zip(&actual_rows)
+            .enumerate()
+            .flat_map(|(y, (expected, actual))| {
+                expected
+                    .iter()
+                    .zip(actual)
+                    .enumerate()
+                    .filter(|(_, (expected, actual))| expected != actual)
+                    .map(move |(x, (expected, actual))| (x, y, *expected, *actual))
+            })
+            .collect::<Vec<_>>();
+
+        if !differing.is_empty() {
+            report.push_str(&format!("{} cell(s) differ:\n", differing.len()));
+        }
+
+        for (x, y, expected, actual) in differing.iter().take(MAX_REPORTED_CELLS) {
+            report.push_str(&format!(
+                "  ({x}, {y}): expected {expected:?}, found {actual:?}\n"
+            ));
+        }
+
+        if differing.len() > MAX_REPORTED_CELLS {
+            report.push_str(&format!(
+                "  ...and {} more\n",
+                differing.len() - MAX_REPORTED_CELLS
+            ));
+        }
+    }
+
+    for line in expected_styles
+        .iter()
+        .filter(|s| !actual_styles.contains(s))
+    {
+        report.push_str(&format!("- {line}\n"));
+    }
+
+    for line in actual_styles
+        .iter()
+        .filter(|s| !expected_styles.contains(s))
+    {
+        report.push_str(&format!("+ {line}\n"));
+    }
+
+    report.push_str("Expected:\n");
+    report.push_str(&indent(expected));
+    report.push_str("Found:\n");
+    report.push_str(&indent(actual));
+
+    Some(report)
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    let root = env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    root.join("snapshots").join(format!("{name}.snap"))
+}
+
+fn write_snapshot(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap_or_else(|e| {
+            panic!(
+                "Failed to create snapshot directory `{}`: {e}",
+                parent.display()
+            )
+        });
+    }
+
+    fs::write(path, contents)
+        .unwrap_or_else(|e| panic!("Failed to write snapshot `{}`: {e}", path.display()));
+}
+
+fn rows(buffer: &Buffer) -> impl Iterator<Item = &[Cell]> {
+    buffer
+        .content
+        .chunks(buffer.area.width.max(1) as usize)
+        .take(buffer.area.height as usize)
+}
+
+/// Joins the symbols of a row, leaving out the cells hidden behind a wide symbol,
+/// so the row is as wide on screen as the buffer.
+fn row_symbols(row: &[Cell]) -> String {
+    let mut symbols = String::new();
+    let mut hidden = 0;
+
+    for cell in row {
+        if hidden > 0 {
+            hidden -= 1;
+            continue;
+        }
+        if cell.skip {
+            continue;
+        }
+
+        symbols.push_str(cell.symbol());
+        hidden = cell.symbol().width().saturating_sub(1);
+    }
+
+    symbols
+}
+
+fn same_style(a: &Cell, b: &Cell) -> bool {
+    a.fg == b.fg && a.bg == b.bg && a.modifier == b.modifier
+}
+
+fn describe_style(cell: &Cell) -> Option<String> {
+    let mut parts = vec![];
+
+    if cell.fg != Color::Reset {
+        parts.push(format!("fg={}", cell.fg));
+    }
+    if cell.bg != Color::Reset {
+        parts.push(format!("bg={}", cell.bg));
+    }
+    if cell.modifier != Modifier::empty() {
+        parts.push(format!("modifier={:?}", cell.modifier));
+    }
+
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Splits a snapshot into its grid of symbols and its style lines.
+fn split_snapshot(snapshot: &str) -> (Vec<Vec<&str>>, Vec<&str>) {
+    let mut grid = vec![];
+    let mut styles = vec![];
+
+    for line in snapshot.lines() {
+        match line
+            .strip_prefix('|')
+            .and_then(|line| line.strip_suffix('|'))
+        {
+            Some(row) => grid.push(symbols(row)),
+            None => styles.push(line),
+        }
+    }
+
+    (grid, styles)
+}
+
+/// Splits a row back into one symbol per cell, the opposite of [`row_symbols`],
+/// with an empty symbol for each cell hidden behind a wide symbol.
+fn symbols(row: &str) -> Vec<&str> {
+    let mut cells = vec![];
+
+    for grapheme in row.graphemes(true) {
+        cells.push(grapheme);
+        cells.extend(std::iter::repeat_n("", grapheme.width().saturating_sub(1)));
+    }
+
+    cells
+}
+
+fn grid_size(grid: &[Vec<&str>]) -> (usize, usize) {
+    (grid.first().map_or(0, Vec::len), grid.len())
+}
+
+fn indent(text: &str) -> String {
+    text.lines().map(|line| format!("  {line}\n")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::{buffer::Buffer, layout::Rect, style::Stylize, text::Line, widgets::Widget};
+
+    use super::*;
+
+    #[test]
+    fn styled_output() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        Line::from(vec!["ab".red().bold(), "cd".into()]).render(buffer.area, &mut buffer);
+
+        assert_eq!(
+            buffer_to_styled_string(&buffer),
+            "|abcd  |\n|      |\nstyle 0:0..2 fg=Red modifier=BOLD\n"
+        );
+    }
+
+    #[test]
+    fn cell_level_report() {
+        let expected = buffer_to_string(&Buffer::with_lines(["abc", "def"]));
+        let actual = buffer_to_string(&Buffer::with_lines(["abc", "dxf"]));
+
+        assert_eq!(diff_snapshots(&expected, &expected), None);
+
+        let report = diff_snapshots(&expected, &actual).unwrap();
+        assert!(report.contains("1 cell(s) differ"));
+        assert!(report.contains("(1, 1): expected \"e\", found \"x\""));
+    }
+
+    #[test]
+    fn wide_and_combined_symbols_keep_their_cells() {
+        let expected = buffer_to_string(&Buffer::with_lines(["日本x", "e\u{301}ab"]));
+        let actual = buffer_to_string(&Buffer::with_lines(["日本y", "e\u{301}ac"]));
+
+        assert_eq!(expected, "|日本x|\n|e\u{301}ab  |\n");
+
+        let report = diff_snapshots(&expected, &actual).unwrap();
+        assert!(report.contains("2 cell(s) differ"));
+        assert!(report.contains("(4, 0): expected \"x\", found \"y\""));
+        assert!(report.contains("(2, 1): expected \"b\", found \"c\""));
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot `never_recorded` is missing")]
+    fn missing_snapshots_fail() {
+        assert_snapshot("never_recorded", &Buffer::with_lines(["abc"]));
+    }
+}
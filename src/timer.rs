@@ -0,0 +1,303 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::State;
+
+/// Whether a [`Timer`] stops once it finishes, or starts counting down again.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMode {
+    #[default]
+    Once,
+    Repeating,
+}
+
+/// Counts down a duration as it is ticked, useful for anything that happens after a delay,
+/// such as toasts expiring or a cursor blinking.
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+    duration: Duration,
+    elapsed: Duration,
+    mode: TimerMode,
+    finished: bool,
+    times_finished_this_tick: u32,
+}
+
+impl Timer {
+    pub fn new(duration: Duration, mode: TimerMode) -> Self {
+        Self {
+            duration,
+            mode,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a timer that finishes a single time, after the duration.
+    pub fn once(duration: Duration) -> Self {
+        Self::new(duration, TimerMode::Once)
+    }
+
+    /// Creates a timer that finishes every time the duration passes.
+    pub fn repeating(duration: Duration) -> Self {
+        Self::new(duration, TimerMode::Repeating)
+    }
+
+    /// Moves the timer forward by the given time, usually [`Time::delta`](crate::states::Time::delta).
+    pub fn tick(&mut self, delta: Duration) -> &mut Self {
+        self.times_finished_this_tick = 0;
+
+        if self.mode == TimerMode::Once && self.finished {
+            return self;
+        }
+
+        self.elapsed += delta;
+
+        if self.elapsed < self.duration {
+            return self;
+        }
+
+        self.finished = true;
+
+        match self.mode {
+            TimerMode::Once => {
+                self.elapsed = self.duration;
+                self.times_finished_this_tick = 1;
+            }
+            TimerMode::Repeating if self.duration.is_zero() => {
+                self.elapsed = Duration::ZERO;
+                self.times_finished_this_tick = 1;
+            }
+            TimerMode::Repeating => {
+                let duration = self.duration.as_nanos();
+                let elapsed = self.elapsed.as_nanos();
+
+                self.times_finished_this_tick = (elapsed / duration) as u32;
+                self.elapsed = Duration::from_nanos((elapsed % duration) as u64);
+            }
+        }
+
+        self
+    }
+
+    /// Returns whether the timer has finished at least once.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns whether the timer finished during the last tick.
+    pub fn just_finished(&self) -> bool {
+        self.times_finished_this_tick > 0
+    }
+
+    /// How many times the timer finished during the last tick,
+    /// which can be more than once for repeating timers with a large tick.
+    pub fn times_finished_this_tick(&self) -> u32 {
+        self.times_finished_this_tick
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration
+    }
+
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// The time counted since the timer was started, or since it last repeated.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The time left until the timer next finishes.
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed)
+    }
+
+    /// How far through its duration the timer is, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+
+        self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+    }
+
+    /// Starts the timer again from the beginning.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.finished = false;
+        self.times_finished_this_tick = 0;
+    }
+}
+
+/// Counts up the time that passes while it isn't paused.
+#[derive(Debug, Clone, Default)]
+pub struct Stopwatch {
+    elapsed: Duration,
+    paused: bool,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the stopwatch forward by the given time, unless it is paused.
+    pub fn tick(&mut self, delta: Duration) -> &mut Self {
+        if !self.paused {
+            self.elapsed += delta;
+        }
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO
+    }
+}
+
+/// The state holding named timers, which the app ticks at the start of every frame.
+///
+/// Timers that only finish once are removed the frame after they finish,
+/// so [`Timers::just_finished`] passes for exactly one frame.
+#[derive(Default, State)]
+pub struct Timers {
+    timers: HashMap<String, Timer>,
+}
+
+impl Timers {
+    /// Starts the given timer, replacing any timer with the same name.
+    pub fn start(&mut self, name: impl Into<String>, timer: Timer) {
+        self.timers.insert(name.into(), timer);
+    }
+
+    /// Stops and removes the timer with the given name, returning it.
+    pub fn cancel(&mut self, name: &str) -> Option<Timer> {
+        self.timers.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Timer> {
+        self.timers.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Timer> {
+        self.timers.get_mut(name)
+    }
+
+    /// Returns whether the timer exists and is still counting down.
+    pub fn running(&self, name: &str) -> bool {
+        self.get(name)
+            .is_some_and(|timer| timer.mode() == TimerMode::Repeating || !timer.finished())
+    }
+
+    /// Returns whether the timer with the given name finished this frame.
+    pub fn just_finished(&self, name: &str) -> bool {
+        self.get(name).is_some_and(Timer::just_finished)
+    }
+
+    /// Returns how long until the next running timer finishes.
+    pub(crate) fn next_finish(&self) -> Option<Duration> {
+        self.timers
+            .values()
+            .filter(|timer| timer.mode() == TimerMode::Repeating || !timer.finished())
+            .map(Timer::remaining)
+            .min()
+    }
+
+    pub(crate) fn tick(&mut self, delta: Duration) {
+        self.timers
+            .retain(|_, timer| timer.mode() == TimerMode::Repeating || !timer.finished());
+
+        for timer in self.timers.values_mut() {
+            timer.tick(delta);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{
+        testing::{AppHarness, Log},
+        *,
+    };
+
+    use super::{Timer, Timers};
+
+    fn start(mut timers: ResMut<Timers>) -> WidgetResult {
+        timers.start("toast", Timer::once(Duration::from_millis(25)));
+        timers.start("blink", Timer::repeating(Duration::from_millis(20)));
+        Ok(())
+    }
+
+    fn check(timers: Res<Timers>, mut log: ResMut<Log>) -> WidgetResult {
+        if timers.just_finished("toast") {
+            log.push("toast");
+        }
+        if timers.just_finished("blink") {
+            log.push("blink");
+        }
+        Ok(())
+    }
+
+    fn poll(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("poll");
+        Ok(())
+    }
+
+    fn delayed(mut log: ResMut<Log>) -> WidgetResult {
+        log.push("delayed");
+        Ok(())
+    }
+
+    #[test]
+    fn timers_finish_on_time() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default())
+                .startup_widgets(start)
+                .widgets(check)
+        });
+        harness.set_frame_time(Duration::from_millis(10));
+
+        harness.step_n(4).unwrap();
+
+        assert_eq!(harness.log().entries(), ["blink", "toast", "blink"]);
+        assert!(!harness.state::<Timers>().running("toast"));
+
+        harness.step().unwrap();
+        assert!(harness.state::<Timers>().get("toast").is_none());
+    }
+
+    #[test]
+    fn widgets_run_on_schedule() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Log::default()).widgets((
+                poll.run_if(every(Duration::from_millis(20))),
+                delayed.run_if(once_after(Duration::from_millis(30))),
+            ))
+        });
+        harness.set_frame_time(Duration::from_millis(10));
+
+        harness.step_n(6).unwrap();
+
+        assert_eq!(harness.log().entries(), ["poll", "delayed", "poll", "poll"]);
+    }
+}
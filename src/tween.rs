@@ -0,0 +1,353 @@
+use std::{any::Any, collections::HashMap, f32::consts::PI, time::Duration};
+
+use ratatui::{layout::Rect, style::Color};
+
+use crate::State;
+
+/// How a tween moves between its start and end values over time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineInOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t`, from `0.0` to `1.0`, onto the curve.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Easing::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::CubicInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+        }
+    }
+}
+
+/// A value that can be smoothly moved between two points.
+pub trait Animatable: Clone + 'static {
+    /// Returns the value `t` of the way from `self` to `to`, where `t` is from `0.0` to `1.0`.
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Animatable for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Animatable for u16 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        (*self as f32).lerp(&(*to as f32), t).round() as u16
+    }
+}
+
+impl Animatable for Rect {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Rect::new(
+            self.x.lerp(&to.x, t),
+            self.y.lerp(&to.y, t),
+            self.width.lerp(&to.width, t),
+            self.height.lerp(&to.height, t),
+        )
+    }
+}
+
+/// Only [`Color::Rgb`] colors are blended,
+/// any other pair of colors switches to the end color once the tween finishes.
+impl Animatable for Color {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        match (self, to) {
+            (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+                let channel =
+                    |from: &u8, to: &u8| (*from as f32).lerp(&(*to as f32), t).round() as u8;
+
+                Color::Rgb(channel(r1, r2), channel(g1, g2), channel(b1, b2))
+            }
+            _ if t >= 1.0 => *to,
+            _ => *self,
+        }
+    }
+}
+
+/// Moves a value from one point to another over a duration.
+///
+/// Tweens started through [`Tweens`] advance on their own each frame,
+/// while any other tween is moved forward with [`Tween::tick`].
+#[derive(Debug, Clone)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+}
+
+impl<T: Animatable> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: Duration::ZERO,
+            easing: Easing::default(),
+        }
+    }
+
+    /// Sets the easing used to move between the values.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Moves the tween forward by the given time, usually [`Time::delta`](crate::states::Time::delta).
+    pub fn tick(&mut self, delta: Duration) -> &mut Self {
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+        self
+    }
+
+    /// How far through its duration the tween is, from `0.0` to `1.0`, before easing.
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+
+        self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+    }
+
+    /// Returns the current value of the tween.
+    pub fn value(&self) -> T {
+        self.from.lerp(&self.to, self.easing.apply(self.progress()))
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Starts moving towards a new value from wherever the tween currently is,
+    /// keeping movement smooth when the target changes part way through.
+    pub fn retarget(&mut self, to: T, duration: Duration) {
+        self.from = self.value();
+        self.to = to;
+        self.duration = duration;
+        self.elapsed = Duration::ZERO;
+    }
+
+    pub fn from(&self) -> &T {
+        &self.from
+    }
+
+    pub fn to(&self) -> &T {
+        &self.to
+    }
+}
+
+/// Allows tweens of any value to be stored and advanced together.
+trait AnyTween {
+    fn tick(&mut self, delta: Duration);
+
+    fn finished(&self) -> bool;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Animatable> AnyTween for Tween<T> {
+    fn tick(&mut self, delta: Duration) {
+        Tween::tick(self, delta);
+    }
+
+    fn finished(&self) -> bool {
+        Tween::finished(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The state holding named tweens, which the app advances at the start of every frame.
+///
+/// Finished tweens are kept, so their final value can still be read.
+#[derive(Default, State)]
+pub struct Tweens {
+    tweens: HashMap<String, Box<dyn AnyTween>>,
+}
+
+impl Tweens {
+    /// Starts the given tween, replacing any tween with the same name.
+    pub fn start<T: Animatable>(&mut self, name: impl Into<String>, tween: Tween<T>) {
+        self.tweens.insert(name.into(), Box::new(tween));
+    }
+
+    /// Moves the named tween towards `to` from its current value,
+    /// or starts it at `to` if it doesn't exist yet.
+    pub fn animate_to<T: Animatable>(
+        &mut self,
+        name: impl Into<String>,
+        to: T,
+        duration: Duration,
+    ) {
+        let name = name.into();
+
+        match self.get_mut::<T>(&name) {
+            Some(tween) => tween.retarget(to, duration),
+            None => self.start(name, Tween::new(to.clone(), to, Duration::ZERO)),
+        }
+    }
+
+    /// Returns the named tween, if it exists and animates a `T`.
+    pub fn get<T: Animatable>(&self, name: &str) -> Option<&Tween<T>> {
+        self.tweens.get(name)?.as_any().downcast_ref()
+    }
+
+    pub fn get_mut<T: Animatable>(&mut self, name: &str) -> Option<&mut Tween<T>> {
+        self.tweens.get_mut(name)?.as_any_mut().downcast_mut()
+    }
+
+    /// Returns the current value of the named tween.
+    pub fn value<T: Animatable>(&self, name: &str) -> Option<T> {
+        self.get::<T>(name).map(Tween::value)
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.tweens.remove(name);
+    }
+
+    /// Returns whether any tween is still moving.
+    pub fn animating(&self) -> bool {
+        self.tweens.values().any(|tween| !tween.finished())
+    }
+
+    pub(crate) fn tick(&mut self, delta: Duration) {
+        for tween in self.tweens.values_mut() {
+            tween.tick(delta);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use ratatui::{layout::Rect, style::Color};
+
+    use crate::{testing::AppHarness, *};
+
+    use super::{Easing, Tween, Tweens};
+
+    fn slide_in(mut tweens: ResMut<Tweens>) -> WidgetResult {
+        tweens.start(
+            "popup",
+            Tween::new(
+                Rect::new(0, 10, 20, 5),
+                Rect::new(0, 0, 20, 5),
+                Duration::from_millis(100),
+            ),
+        );
+        tweens.start(
+            "fade",
+            Tween::new(
+                Color::Rgb(0, 0, 0),
+                Color::Rgb(200, 100, 0),
+                Duration::from_millis(100),
+            )
+            .easing(Easing::QuadIn),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tweens_advance_each_frame() {
+        let mut harness = AppHarness::new(1, 1).app(|app| app.startup_widgets(slide_in));
+        harness.set_frame_time(Duration::from_millis(50));
+
+        harness.step().unwrap();
+        {
+            let tweens = harness.state::<Tweens>();
+            assert_eq!(tweens.value("popup"), Some(Rect::new(0, 5, 20, 5)));
+            assert_eq!(tweens.value("fade"), Some(Color::Rgb(50, 25, 0)));
+            assert!(tweens.animating());
+        }
+
+        harness.step_n(2).unwrap();
+        let tweens = harness.state::<Tweens>();
+        assert_eq!(tweens.value("popup"), Some(Rect::new(0, 0, 20, 5)));
+        assert!(!tweens.animating());
+    }
+
+    #[derive(Default, State)]
+    struct Frames(usize);
+
+    fn slide_in_once(mut tweens: ResMut<Tweens>) -> WidgetResult {
+        if tweens.get::<f32>("slide").is_none() {
+            tweens.start("slide", Tween::new(0.0, 1.0, Duration::from_millis(50)));
+        }
+        Ok(())
+    }
+
+    fn exit_when_slid_in(
+        tweens: Res<Tweens>,
+        mut frames: ResMut<Frames>,
+        mut events: ResMut<Events>,
+    ) -> WidgetResult {
+        frames.0 += 1;
+        if tweens.value::<f32>("slide") == Some(1.0) {
+            events.exit_with(frames.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn reactive_apps_redraw_each_clock_tick_while_animating() {
+        let terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(1, 1)).unwrap();
+        let mut app = App::from_terminal(terminal, 10)
+            .run_mode(RunMode::Reactive)
+            .states(Frames::default())
+            .widgets((slide_in_once, exit_when_slid_in));
+
+        // Wakes the app up late if the tween doesn't, so a failure doesn't hang.
+        let late = app.event_sender::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(1));
+            let _ = late.send(());
+        });
+
+        let started = std::time::Instant::now();
+        let exit = app.run_returning::<usize>().unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        // About one frame every 10ms for 50ms, rather than one frame as fast as possible.
+        assert!(exit.value.is_some_and(|frames| (3..=10).contains(&frames)));
+    }
+
+    #[test]
+    fn retargeting_starts_from_current_value() {
+        let mut tween = Tween::new(0.0, 10.0, Duration::from_secs(1));
+        tween.tick(Duration::from_millis(500));
+        tween.retarget(0.0, Duration::from_secs(1));
+
+        assert_eq!(tween.value(), 5.0);
+        tween.tick(Duration::from_millis(500));
+        assert_eq!(tween.value(), 2.5);
+    }
+}
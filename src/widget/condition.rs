@@ -0,0 +1,278 @@
+use std::{marker::PhantomData, time::Duration};
+
+use crossterm::event::KeyCode;
+
+use crate::states::States;
+use crate::widget::{id::WidgetId, order::WidgetOrder, param::WidgetParam, Widget};
+use crate::{redraw::Redraw, states::Time, Events, Res, WidgetResult};
+
+/// A check that decides whether a widget should run this frame.
+pub trait Condition {
+    fn check(&mut self, states: &States) -> bool;
+}
+
+/// Allows all versions of a function with the parameters
+/// that work within a widget, and that return a `bool`, to be converted into a condition.
+pub trait IntoCondition<Input, Data> {
+    type Condition: Condition;
+
+    fn into_condition(self) -> Self::Condition;
+}
+
+/// Marks conditions that were created by hand instead of from a function.
+pub struct IsCondition;
+
+impl<C: Condition> IntoCondition<(), IsCondition> for C {
+    type Condition = C;
+
+    fn into_condition(self) -> Self::Condition {
+        self
+    }
+}
+
+// A condition that comes from specifically a function.
+pub struct FunctionCondition<Input, F> {
+    f: F,
+    marker: PhantomData<fn() -> Input>,
+}
+
+macro_rules! impl_condition_for_func {
+    ($($item:ident)*) => {
+        impl<Func, $($item),*> Condition for FunctionCondition<($($item,)*), Func>
+        where
+                for<'a, 'b> &'a mut Func:
+                    FnMut( $($item),* ) -> bool +
+                    FnMut( $(<$item as WidgetParam>::Item<'b>),* ) -> bool,
+            $($item: WidgetParam + 'static),*
+        {
+            #[inline]
+            #[allow(non_snake_case, unused_variables)]
+            fn check(&mut self, states: &States) -> bool {
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<$($item),*>(
+                    mut f: impl FnMut($($item),*) -> bool,
+                    $($item: $item,)*
+                ) -> bool {
+                    f($($item),*)
+                }
+
+                $(
+                    let $item = $item::retrieve(states);
+                )*
+
+                call_inner(&mut self.f, $($item),*)
+            }
+        }
+
+        impl<Func, $($item),*> IntoCondition<($($item,)*), ()> for Func
+        where
+                for<'a, 'b> &'a mut Func:
+                    FnMut( $($item),* ) -> bool +
+                    FnMut( $(<$item as WidgetParam>::Item<'b>),* ) -> bool,
+            $($item: WidgetParam + 'static),*
+        {
+            type Condition = FunctionCondition<($($item,)*), Self>;
+
+            #[inline]
+            fn into_condition(self) -> Self::Condition {
+                FunctionCondition {
+                    f: self,
+                    marker: Default::default(),
+                }
+            }
+        }
+    };
+}
+
+impl_condition_for_func! {}
+impl_condition_for_func! { A }
+impl_condition_for_func! { A B }
+impl_condition_for_func! { A B C }
+impl_condition_for_func! { A B C D }
+impl_condition_for_func! { A B C D E }
+impl_condition_for_func! { A B C D E F }
+impl_condition_for_func! { A B C D E F G }
+impl_condition_for_func! { A B C D E F G H }
+impl_condition_for_func! { A B C D E F G H I }
+impl_condition_for_func! { A B C D E F G H I J }
+impl_condition_for_func! { A B C D E F G H I J K }
+impl_condition_for_func! { A B C D E F G H I J K L }
+
+/// A widget that only runs when its condition passes.
+/// Created with [`IntoWidget::run_if`](crate::widget::into_widget::IntoWidget::run_if).
+pub struct RunIf<W, C> {
+    widget: W,
+    condition: C,
+}
+
+impl<W, C> RunIf<W, C> {
+    pub fn new(widget: W, condition: C) -> Self {
+        Self { widget, condition }
+    }
+}
+
+impl<W: Widget, C: Condition> Widget for RunIf<W, C> {
+    fn call(&mut self, states: &mut States) -> WidgetResult {
+        if self.condition.check(states) {
+            self.widget.call(states)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn order(&self) -> WidgetOrder {
+        self.widget.order()
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.widget.id()
+    }
+}
+
+// ---------- Built In Conditions --------- //
+
+/// Passes when any event was received this frame.
+pub fn on_event(events: Res<Events>) -> bool {
+    !events.is_empty()
+}
+
+/// Passes when the given key was pressed this frame.
+pub fn on_key(code: KeyCode) -> OnKey {
+    OnKey(code)
+}
+
+/// The condition created by [`on_key`].
+pub struct OnKey(KeyCode);
+
+impl Condition for OnKey {
+    fn check(&mut self, states: &States) -> bool {
+        Res::<Events>::retrieve(states).key(self.0)
+    }
+}
+
+/// Passes once every time the given duration passes,
+/// counted from the frame the condition is first checked.
+///
+/// Passes at most once per frame, so durations shorter than a frame behave like every frame.
+pub fn every(period: Duration) -> Every {
+    Every { period, next: None }
+}
+
+/// The condition created by [`every`].
+pub struct Every {
+    period: Duration,
+    next: Option<Duration>,
+}
+
+impl Condition for Every {
+    fn check(&mut self, states: &States) -> bool {
+        let time = Res::<Time>::retrieve(states);
+        let elapsed = time.elapsed();
+
+        let next = self
+            .next
+            .get_or_insert_with(|| elapsed.saturating_sub(time.delta()) + self.period);
+
+        if self.period.is_zero() {
+            return elapsed >= *next;
+        }
+
+        let passed = elapsed >= *next;
+        while *next <= elapsed {
+            *next += self.period;
+        }
+
+        // Wakes reactive apps back up when the condition next passes.
+        Res::<Redraw>::retrieve(states).request_redraw_in(*next - elapsed);
+
+        passed
+    }
+}
+
+/// Passes a single time, once the given duration has passed,
+/// counted from the frame the condition is first checked.
+pub fn once_after(delay: Duration) -> OnceAfter {
+    OnceAfter {
+        delay,
+        deadline: None,
+        done: false,
+    }
+}
+
+/// The condition created by [`once_after`].
+pub struct OnceAfter {
+    delay: Duration,
+    deadline: Option<Duration>,
+    done: bool,
+}
+
+impl Condition for OnceAfter {
+    fn check(&mut self, states: &States) -> bool {
+        if self.done {
+            return false;
+        }
+
+        let time = Res::<Time>::retrieve(states);
+        let elapsed = time.elapsed();
+
+        let deadline = *self
+            .deadline
+            .get_or_insert_with(|| elapsed.saturating_sub(time.delta()) + self.delay);
+
+        self.done = elapsed >= deadline;
+
+        if !self.done {
+            Res::<Redraw>::retrieve(states).request_redraw_in(deadline - elapsed);
+        }
+
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::KeyCode;
+
+    use crate::{testing::AppHarness, *};
+
+    use super::on_key;
+
+    #[derive(Default, State)]
+    struct Mode {
+        editing: bool,
+        edits: u32,
+    }
+
+    fn editing(mode: Res<Mode>) -> bool {
+        mode.editing
+    }
+
+    fn edit(mut mode: ResMut<Mode>) -> WidgetResult {
+        mode.edits += 1;
+        Ok(())
+    }
+
+    fn toggle(mut mode: ResMut<Mode>) -> WidgetResult {
+        mode.editing = !mode.editing;
+        Ok(())
+    }
+
+    #[test]
+    fn widgets_only_run_when_condition_passes() {
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states(Mode::default())
+                .widgets((toggle.run_if(on_key(KeyCode::Tab)), edit.run_if(editing)))
+        });
+
+        harness.step().unwrap();
+        assert_eq!(harness.state::<Mode>().edits, 0);
+
+        harness.push_key(KeyCode::Tab);
+        harness.step_n(3).unwrap();
+        assert_eq!(harness.state::<Mode>().edits, 3);
+
+        harness.push_key(KeyCode::Tab);
+        harness.step_n(2).unwrap();
+        assert_eq!(harness.state::<Mode>().edits, 3);
+    }
+}
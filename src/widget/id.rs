@@ -0,0 +1,120 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::states::States;
+use crate::widget::{order::WidgetOrder, Widget};
+use crate::WidgetResult;
+
+/// A handle to a single registered widget,
+/// used to disable, enable, replace or remove it while the app is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WidgetId(u64);
+
+impl WidgetId {
+    /// Creates a new id, which is different from every other id.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A widget with an id assigned to it.
+/// Created with [`IntoWidget::with_id`](crate::widget::into_widget::IntoWidget::with_id).
+pub struct Identified<W> {
+    widget: W,
+    id: WidgetId,
+}
+
+impl<W> Identified<W> {
+    pub fn new(widget: W, id: WidgetId) -> Self {
+        Self { widget, id }
+    }
+}
+
+impl<W: Widget> Widget for Identified<W> {
+    fn call(&mut self, states: &mut States) -> WidgetResult {
+        self.widget.call(states)
+    }
+
+    fn order(&self) -> WidgetOrder {
+        self.widget.order()
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        Some(self.id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::KeyCode;
+
+    use crate::{testing::AppHarness, *};
+
+    use super::WidgetId;
+
+    #[derive(State)]
+    struct Ids {
+        counter: WidgetId,
+    }
+
+    #[derive(Default, State)]
+    struct Count(i32);
+
+    fn increment(mut count: ResMut<Count>) -> WidgetResult {
+        count.0 += 1;
+        Ok(())
+    }
+
+    fn decrement(mut count: ResMut<Count>) -> WidgetResult {
+        count.0 -= 1;
+        Ok(())
+    }
+
+    fn controls(events: Res<Events>, ids: Res<Ids>, mut commands: Commands) -> WidgetResult {
+        if events.key(KeyCode::Char('d')) {
+            commands.disable_widget(ids.counter);
+        }
+        if events.key(KeyCode::Char('e')) {
+            commands.enable_widget(ids.counter);
+        }
+        if events.key(KeyCode::Char('r')) {
+            commands.replace_widget(ids.counter, decrement);
+        }
+        if events.key(KeyCode::Char('x')) {
+            commands.remove_widget(ids.counter);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn widgets_are_toggled_by_id() {
+        let counter = WidgetId::new();
+
+        let mut harness = AppHarness::new(1, 1).app(|app| {
+            app.states((Count::default(), Ids { counter }))
+                .add_widgets(Stage::PreUpdate, controls)
+                .widgets(increment.with_id(counter))
+        });
+
+        harness.step_n(2).unwrap();
+        assert_eq!(harness.state::<Count>().0, 2);
+
+        harness.push_key(KeyCode::Char('d'));
+        harness.step_n(2).unwrap();
+        assert_eq!(harness.state::<Count>().0, 2);
+
+        harness.push_key(KeyCode::Char('e'));
+        harness.step().unwrap();
+        assert_eq!(harness.state::<Count>().0, 3);
+
+        harness.push_key(KeyCode::Char('r'));
+        harness.step_n(2).unwrap();
+        assert_eq!(harness.state::<Count>().0, 1);
+
+        harness.push_key(KeyCode::Char('x'));
+        harness.step_n(2).unwrap();
+        assert_eq!(harness.state::<Count>().0, 1);
+    }
+}
@@ -1,5 +1,8 @@
 use std::marker::PhantomData;
 
+use crate::widget::condition::{IntoCondition, RunIf};
+use crate::widget::id::{Identified, WidgetId};
+use crate::widget::order::Ordered;
 use crate::widget::Widget;
 
 /// Allows all versions of a function with the parameters
@@ -8,4 +11,61 @@ pub trait IntoWidget<Input, Data> {
     type Widget: Widget;
 
     fn into_widget(self) -> Self::Widget;
+
+    /// Only runs the widget on frames where the given condition passes.
+    /// The condition is a function that takes in the same parameters as a widget,
+    /// but returns a `bool`.
+    fn run_if<CInput, CData, C: IntoCondition<CInput, CData>>(
+        self,
+        condition: C,
+    ) -> RunIf<Self::Widget, C::Condition>
+    where
+        Self: Sized,
+    {
+        RunIf::new(self.into_widget(), condition.into_condition())
+    }
+
+    /// Gives the widget the label `L`, allowing other widgets to be ordered around it.
+    fn label<L: 'static>(self) -> Ordered<Self::Widget>
+    where
+        Self: Sized,
+    {
+        Ordered::new(self.into_widget()).label::<L>()
+    }
+
+    /// Runs the widget before every widget labeled `L` in the same stage.
+    fn before<L: 'static>(self) -> Ordered<Self::Widget>
+    where
+        Self: Sized,
+    {
+        Ordered::new(self.into_widget()).before::<L>()
+    }
+
+    /// Runs the widget after every widget labeled `L` in the same stage.
+    fn after<L: 'static>(self) -> Ordered<Self::Widget>
+    where
+        Self: Sized,
+    {
+        Ordered::new(self.into_widget()).after::<L>()
+    }
+
+    /// Assigns the given id to the widget, allowing it to be disabled, enabled,
+    /// replaced or removed while the app is running.
+    fn with_id(self, id: WidgetId) -> Identified<Self::Widget>
+    where
+        Self: Sized,
+    {
+        Identified::new(self.into_widget(), id)
+    }
+}
+
+/// Marks widgets that were created by hand, or by combining other widgets.
+pub struct IsWidget;
+
+impl<W: Widget> IntoWidget<(), IsWidget> for W {
+    type Widget = W;
+
+    fn into_widget(self) -> Self::Widget {
+        self
+    }
 }
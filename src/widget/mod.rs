@@ -1,10 +1,15 @@
 pub mod param;
 
+pub mod condition;
 pub mod function_widget;
+pub mod id;
 pub mod into_widget;
 pub mod into_widget_set;
+pub mod order;
 
 use crate::{states::States, WidgetFrame};
+
+use self::{id::WidgetId, order::WidgetOrder};
 use std::{
     any::{Any, TypeId},
     collections::HashMap,
@@ -17,6 +22,8 @@ pub enum WidgetError {
     Io(#[from] std::io::Error),
     #[error("Chunk doesn't exist")]
     ChunkError,
+    #[error("Widget ordering contains a cycle between {0}")]
+    OrderCycle(String),
     #[error(transparent)]
     Misc(#[from] anyhow::Error),
 }
@@ -27,4 +34,14 @@ pub type WidgetResult = Result<(), WidgetError>;
 /// A widget that can be called.
 pub trait Widget {
     fn call(&mut self, states: &mut States) -> WidgetResult;
+
+    /// The labels and ordering constraints of the widget.
+    fn order(&self) -> WidgetOrder {
+        WidgetOrder::default()
+    }
+
+    /// The id assigned to the widget, if any.
+    fn id(&self) -> Option<WidgetId> {
+        None
+    }
 }
@@ -0,0 +1,210 @@
+use std::any::{type_name, TypeId};
+
+use crate::states::States;
+use crate::widget::{id::WidgetId, Widget, WidgetError};
+use crate::WidgetResult;
+
+/// A name that widgets can be given, so other widgets can be ordered around them.
+/// Labels are types, in the same way that chunks are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Label {
+    id: TypeId,
+    name: &'static str,
+}
+
+impl Label {
+    /// Returns the label for the given type.
+    pub fn of<T: 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: type_name::<T>(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// The labels a widget has, and the labels that it must run before or after.
+#[derive(Debug, Clone, Default)]
+pub struct WidgetOrder {
+    pub labels: Vec<Label>,
+    pub before: Vec<Label>,
+    pub after: Vec<Label>,
+}
+
+impl WidgetOrder {
+    /// Adds all of the labels and constraints of the other order to this one.
+    pub fn extend(&mut self, other: &WidgetOrder) {
+        self.labels.extend(&other.labels);
+        self.before.extend(&other.before);
+        self.after.extend(&other.after);
+    }
+
+    /// Returns whether a widget with this order must run before a widget with the other order.
+    fn runs_before(&self, other: &WidgetOrder) -> bool {
+        self.before.iter().any(|label| other.labels.contains(label))
+            || other.after.iter().any(|label| self.labels.contains(label))
+    }
+
+    fn describe(&self) -> String {
+        match self.labels.first() {
+            Some(label) => format!("`{}`", label.name()),
+            None => "an unlabeled widget".to_string(),
+        }
+    }
+}
+
+/// A widget with extra ordering constraints.
+/// Created with [`IntoWidget::label`](crate::widget::into_widget::IntoWidget::label),
+/// [`IntoWidget::before`](crate::widget::into_widget::IntoWidget::before) or
+/// [`IntoWidget::after`](crate::widget::into_widget::IntoWidget::after).
+pub struct Ordered<W> {
+    widget: W,
+    order: WidgetOrder,
+}
+
+impl<W> Ordered<W> {
+    pub fn new(widget: W) -> Self {
+        Self {
+            widget,
+            order: WidgetOrder::default(),
+        }
+    }
+
+    /// Gives the widget the label `L`.
+    pub fn label<L: 'static>(mut self) -> Self {
+        self.order.labels.push(Label::of::<L>());
+        self
+    }
+
+    /// Runs the widget before every widget labeled `L` in the same stage.
+    pub fn before<L: 'static>(mut self) -> Self {
+        self.order.before.push(Label::of::<L>());
+        self
+    }
+
+    /// Runs the widget after every widget labeled `L` in the same stage.
+    pub fn after<L: 'static>(mut self) -> Self {
+        self.order.after.push(Label::of::<L>());
+        self
+    }
+}
+
+impl<W: Widget> Widget for Ordered<W> {
+    fn call(&mut self, states: &mut States) -> WidgetResult {
+        self.widget.call(states)
+    }
+
+    fn order(&self) -> WidgetOrder {
+        let mut order = self.widget.order();
+        order.extend(&self.order);
+        order
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.widget.id()
+    }
+}
+
+/// Sorts the given items so that every ordering constraint is met,
+/// keeping the order they were given in wherever there are no constraints.
+///
+/// Returns an error if the constraints contain a cycle.
+pub(crate) fn sort_by_order<T>(
+    items: Vec<T>,
+    order: impl Fn(&T) -> &WidgetOrder,
+) -> Result<Vec<T>, WidgetError> {
+    let count = items.len();
+
+    let mut dependents = vec![vec![]; count];
+    let mut dependencies = vec![0usize; count];
+
+    for (i, a) in items.iter().enumerate() {
+        for (j, b) in items.iter().enumerate() {
+            if i != j && order(a).runs_before(order(b)) {
+                dependents[i].push(j);
+                dependencies[j] += 1;
+            }
+        }
+    }
+
+    let mut sorted = Vec::with_capacity(count);
+    let mut done = vec![false; count];
+
+    while let Some(next) = (0..count).find(|&i| !done[i] && dependencies[i] == 0) {
+        done[next] = true;
+        sorted.push(next);
+
+        for &dependent in &dependents[next] {
+            dependencies[dependent] -= 1;
+        }
+    }
+
+    if sorted.len() != count {
+        let cycle = (0..count)
+            .filter(|&i| !done[i])
+            .map(|i| order(&items[i]).describe())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        return Err(WidgetError::OrderCycle(cycle));
+    }
+
+    let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+
+    Ok(sorted
+        .into_iter()
+        .map(|i| {
+            items[i]
+                .take()
+                .expect("Each item should only be sorted once")
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sort_by_order, Label, WidgetOrder};
+
+    struct A;
+    struct B;
+    struct C;
+
+    fn order(labels: &[Label], before: &[Label], after: &[Label]) -> WidgetOrder {
+        WidgetOrder {
+            labels: labels.to_vec(),
+            before: before.to_vec(),
+            after: after.to_vec(),
+        }
+    }
+
+    #[test]
+    fn constraints_are_met() {
+        let items = vec![
+            ("c", order(&[Label::of::<C>()], &[], &[Label::of::<B>()])),
+            ("free", WidgetOrder::default()),
+            ("b", order(&[Label::of::<B>()], &[], &[])),
+            ("a", order(&[Label::of::<A>()], &[Label::of::<B>()], &[])),
+        ];
+
+        let sorted = sort_by_order(items, |(_, order)| order).unwrap();
+        let names = sorted.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+
+        assert_eq!(names, ["free", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let items = vec![
+            order(&[Label::of::<A>()], &[], &[Label::of::<B>()]),
+            order(&[Label::of::<B>()], &[], &[Label::of::<A>()]),
+        ];
+
+        let error = sort_by_order(items, |order| order).unwrap_err();
+
+        assert!(error.to_string().contains("::A`"));
+        assert!(error.to_string().contains("::B`"));
+    }
+}
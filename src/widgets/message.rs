@@ -3,15 +3,15 @@ use std::{collections::VecDeque, time::Duration};
 use ratatui::buffer::Buffer;
 use ratatui::widgets::{Block, Borders, Paragraph, Widget};
 
-use crate::{states::Time, Chunks, WidgetFrame, WidgetResult};
+use crate::{redraw::Redraw, states::Time, timer::Timer, Chunks, WidgetFrame, WidgetResult};
 
 pub struct MessageChunk;
 
-use crate::{App, Res, ResMut, State};
+use crate::{App, Res, ResMut, Stage, State};
 
 #[derive(State)]
 pub struct MessageState {
-    active_message: Option<(String, Duration)>,
+    active_message: Option<(String, Timer)>,
     messages: VecDeque<(String, Duration)>,
     block: Block<'static>,
 }
@@ -42,34 +42,36 @@ impl MessageState {
 
 /// A Timed Message Render
 pub fn message(
-    mut frame: ResMut<Buffer>,
-    timer: Res<Time>,
+    mut frame: ResMut<WidgetFrame>,
+    time: Res<Time>,
     chunks: Res<Chunks>,
     mut messages: ResMut<MessageState>,
+    redraw: Res<Redraw>,
 ) -> WidgetResult {
-    let time = timer.frame_time();
     let rect = chunks.get_chunk::<MessageChunk>()?;
 
     if let Some(message) = &mut messages.active_message {
-        if message.1.as_millis() > time.as_millis() {
-            message.1 -= time;
-        } else {
-            message.1 = Duration::ZERO;
-        }
-
-        if message.1.is_zero() {
+        if message.1.tick(time.delta()).finished() {
             messages.active_message = None;
+
+            // The next message is shown in the next frame.
+            if !messages.messages.is_empty() {
+                redraw.request_redraw();
+            }
         } else {
-            Paragraph::new(message.0.clone())
-                .block(messages.block.clone())
-                .render(rect, &mut frame)
+            // Wake reactive apps back up to hide the message once it expires.
+            redraw.request_redraw_in(message.1.remaining());
+
+            frame.render_widget(
+                Paragraph::new(message.0.clone()).block(messages.block.clone()),
+                rect,
+            )
         }
-    } else if let Some(message) = messages.messages.pop_front() {
-        messages.active_message = Some(message.clone());
+    } else if let Some((message, duration)) = messages.messages.pop_front() {
+        messages.active_message = Some((message.clone(), Timer::once(duration)));
+        redraw.request_redraw_in(duration);
 
-        Paragraph::new(message.0)
-            .block(messages.block.clone())
-            .render(rect, &mut frame);
+        frame.render_widget(Paragraph::new(message).block(messages.block.clone()), rect);
     }
 
     Ok(())
@@ -79,5 +81,38 @@ use crate::set::Set;
 
 #[set]
 pub fn Message(app: App) -> App {
-    app.widgets(message).states(MessageState::default())
+    app.add_widgets(Stage::Render, message)
+        .states(MessageState::default())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use ratatui::layout::Rect;
+
+    use crate::{testing::AppHarness, *};
+
+    use super::{Message, MessageChunk, MessageState};
+
+    fn chunk(mut chunks: ResMut<Chunks>) -> WidgetResult {
+        chunks.register_chunk::<MessageChunk>(Rect::new(1, 1, 20, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn renders_until_expired() {
+        let mut harness = AppHarness::new(22, 5).app(|app| app.widgets(chunk).sets(Message));
+        harness.set_frame_time(Duration::from_millis(100));
+
+        harness
+            .state_mut::<MessageState>()
+            .render_message("Hello!", Duration::from_millis(150));
+
+        harness.step().unwrap();
+        harness.assert_snapshot("message");
+
+        harness.step_n(2).unwrap();
+        harness.assert_snapshot("message_expired");
+    }
 }